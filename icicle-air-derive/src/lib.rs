@@ -0,0 +1,246 @@
+//! `#[derive(AirColumns)]`, for structs naming the columns of an AIR's trace row.
+//!
+//! The struct must be `#[repr(C)]`, have named fields, and be generic over exactly one type, the
+//! cell type `T`; it may have const parameters. Each field must be a `T`, an array of fields, or
+//! another `AirColumns` struct over `T`. The derive implements `icicle_trace::AirColumns<T>`, with
+//! the column count and the hierarchical column names, and `Borrow`/`BorrowMut` from a row `[T]`.
+
+use proc_macro::TokenStream;
+use proc_macro2::{Ident, TokenStream as TokenStream2};
+use quote::{format_ident, quote};
+use syn::spanned::Spanned;
+use syn::{parse_macro_input, Data, DeriveInput, Error, Fields, GenericParam, Type};
+
+/// Derives `AirColumns` for a struct of columns:
+///
+/// ```
+/// use icicle_trace::AirColumns;
+///
+/// #[repr(C)]
+/// #[derive(AirColumns)]
+/// pub struct Cols<T> {
+///     a: T,
+///     b: [T; 2],
+/// }
+///
+/// assert_eq!(<Cols<u8> as AirColumns<u8>>::NUM_COLUMNS, 3);
+/// ```
+///
+/// The struct must be `#[repr(C)]`, so that its fields are laid out in order:
+///
+/// ```compile_fail
+/// use icicle_trace::AirColumns;
+///
+/// #[derive(AirColumns)]
+/// pub struct Cols<T> {
+///     a: T,
+/// }
+/// ```
+///
+/// It must be generic over exactly one type:
+///
+/// ```compile_fail
+/// use icicle_trace::AirColumns;
+///
+/// #[repr(C)]
+/// #[derive(AirColumns)]
+/// pub struct Cols<T, U> {
+///     a: T,
+///     b: U,
+/// }
+/// ```
+///
+/// Its fields must be named:
+///
+/// ```compile_fail
+/// use icicle_trace::AirColumns;
+///
+/// #[repr(C)]
+/// #[derive(AirColumns)]
+/// pub struct Cols<T>(T, T);
+/// ```
+///
+/// And each field must be a cell, an array, or another `AirColumns` struct:
+///
+/// ```compile_fail
+/// use icicle_trace::AirColumns;
+///
+/// #[repr(C)]
+/// #[derive(AirColumns)]
+/// pub struct Cols<T> {
+///     pair: (T, T),
+/// }
+/// ```
+#[proc_macro_derive(AirColumns)]
+pub fn derive_air_columns(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+fn expand(input: &DeriveInput) -> Result<TokenStream2, Error> {
+    let name = &input.ident;
+    if !is_repr_c(input) {
+        return Err(Error::new(
+            name.span(),
+            "AirColumns structs must be #[repr(C)]",
+        ));
+    }
+
+    let mut type_params = input
+        .generics
+        .params
+        .iter()
+        .filter_map(|param| match param {
+            GenericParam::Type(param) => Some(&param.ident),
+            _ => None,
+        });
+    let cell = match (type_params.next(), type_params.next()) {
+        (Some(cell), None) => cell,
+        _ => {
+            return Err(Error::new(
+                input.generics.span(),
+                "AirColumns structs must be generic over exactly one type, the cell type",
+            ))
+        }
+    };
+
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(Error::new(
+                    name.span(),
+                    "AirColumns structs must have named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(Error::new(
+                name.span(),
+                "AirColumns can only be derived for structs",
+            ))
+        }
+    };
+
+    let mut widths = Vec::new();
+    let mut names = Vec::new();
+    for field in fields {
+        let field_name = field.ident.as_ref().expect("fields are named").to_string();
+        widths.push(width(&field.ty, cell)?);
+        let prefix = quote!(::icicle_trace::columns::__private::String::from(#field_name));
+        names.push(push_names(&field.ty, cell, prefix, 0)?);
+    }
+
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let private = quote!(::icicle_trace::columns::__private);
+    Ok(quote! {
+        unsafe impl #impl_generics ::icicle_trace::columns::AirColumns<#cell>
+            for #name #ty_generics #where_clause
+        {
+            const NUM_COLUMNS: usize = 0 #(+ #widths)*;
+
+            fn column_names() -> #private::Vec<#private::String> {
+                let mut names = #private::Vec::with_capacity(
+                    <Self as ::icicle_trace::columns::AirColumns<#cell>>::NUM_COLUMNS,
+                );
+                #(#names)*
+                names
+            }
+        }
+
+        impl #impl_generics ::core::borrow::Borrow<#name #ty_generics> for [#cell] #where_clause {
+            fn borrow(&self) -> &#name #ty_generics {
+                ::icicle_trace::columns::borrow_columns(self)
+            }
+        }
+
+        impl #impl_generics ::core::borrow::BorrowMut<#name #ty_generics> for [#cell] #where_clause {
+            fn borrow_mut(&mut self) -> &mut #name #ty_generics {
+                ::icicle_trace::columns::borrow_columns_mut(self)
+            }
+        }
+    })
+}
+
+fn is_repr_c(input: &DeriveInput) -> bool {
+    input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("repr"))
+        .any(|attr| {
+            let mut is_c = false;
+            let _ = attr.parse_nested_meta(|meta| {
+                is_c |= meta.path.is_ident("C");
+                Ok(())
+            });
+            is_c
+        })
+}
+
+fn is_cell(ty: &Type, cell: &Ident) -> bool {
+    match ty {
+        Type::Path(path) => path.qself.is_none() && path.path.is_ident(cell),
+        _ => false,
+    }
+}
+
+/// The number of columns in a field of type `ty`.
+fn width(ty: &Type, cell: &Ident) -> Result<TokenStream2, Error> {
+    match ty {
+        _ if is_cell(ty, cell) => Ok(quote!(1)),
+        Type::Array(array) => {
+            let len = &array.len;
+            let elem = width(&array.elem, cell)?;
+            Ok(quote!((#len) * (#elem)))
+        }
+        Type::Path(_) => {
+            Ok(quote!(<#ty as ::icicle_trace::columns::AirColumns<#cell>>::NUM_COLUMNS))
+        }
+        Type::Paren(paren) => width(&paren.elem, cell),
+        _ => Err(unsupported(ty)),
+    }
+}
+
+/// Code pushing the names of the columns in a field of type `ty` onto `names`, where `prefix`
+/// evaluates to the name of the field.
+fn push_names(
+    ty: &Type,
+    cell: &Ident,
+    prefix: TokenStream2,
+    depth: usize,
+) -> Result<TokenStream2, Error> {
+    let private = quote!(::icicle_trace::columns::__private);
+    match ty {
+        _ if is_cell(ty, cell) => Ok(quote!(names.push(#prefix);)),
+        Type::Array(array) => {
+            let len = &array.len;
+            let index = format_ident!("i{}", depth);
+            let prefix_var = format_ident!("prefix{}", depth);
+            let inner = push_names(&array.elem, cell, quote!(#prefix_var.clone()), depth + 1)?;
+            Ok(quote! {
+                let #prefix_var = #prefix;
+                for #index in 0..(#len) {
+                    let #prefix_var = #private::format!("{}[{}]", #prefix_var, #index);
+                    #inner
+                }
+            })
+        }
+        Type::Path(_) => Ok(quote! {
+            let prefix = #prefix;
+            for name in <#ty as ::icicle_trace::columns::AirColumns<#cell>>::column_names() {
+                names.push(#private::format!("{}.{}", prefix, name));
+            }
+        }),
+        Type::Paren(paren) => push_names(&paren.elem, cell, prefix, depth),
+        _ => Err(unsupported(ty)),
+    }
+}
+
+fn unsupported(ty: &Type) -> Error {
+    Error::new(
+        ty.span(),
+        "AirColumns fields must be the cell type, arrays, or other AirColumns structs",
+    )
+}
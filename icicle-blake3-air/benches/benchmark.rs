@@ -25,7 +25,7 @@ pub fn try_load_and_set_backend_gpu() {
 fn test_trace(n: usize) {
     let blake3_air = Blake3Air {};
     let trace = blake3_air.generate_trace_rows::<Fr>(n);
-    // let symbolic_constraints = get_symbolic_constraints::<Fr, Blake3Air>(&Blake3Air {}, 0, 0);
+    // let symbolic_constraints = get_symbolic_constraints::<Fr, Blake3Air>(&Blake3Air {});
 }
 
 const SAMPLES: usize = 1 << 15;
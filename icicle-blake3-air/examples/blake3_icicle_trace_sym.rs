@@ -8,7 +8,7 @@ use p3_matrix::Matrix;
 fn test_trace(n: usize) {
     let blake3_air = Blake3Air {};
     let trace = blake3_air.generate_trace_rows::<Fr>(n);
-    let symbolic_constraints = get_symbolic_constraints::<Fr, Blake3Air>(&Blake3Air {}, 0, 0);
+    let symbolic_constraints = get_symbolic_constraints::<Fr, Blake3Air>(&Blake3Air {});
 
     let constraint_degree = symbolic_constraints
     .iter()
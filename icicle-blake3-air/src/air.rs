@@ -2,6 +2,7 @@
 // Original authors: Plonky3 authors, 2022
 // Modifications by Ingonyama, 2025
 
+use alloc::vec;
 use alloc::vec::Vec;
 use core::borrow::Borrow;
 
@@ -9,17 +10,20 @@ use icicle_core::traits::Arithmetic;
 use icicle_core::field::Field;
 use icicle_core::bignum::BigNum;
 use icicle_trace::utils::{add2, add3, xor_32_shift};
-use icicle_trace::{Air, AirBuilder, BaseAir};
+use icicle_trace::{
+    Air, AirBuilder, BaseAir, BaseAirWithPublicValues, FieldRequirement, Interaction,
+    VirtualPairCol,
+};
 use itertools::izip;
 use p3_matrix::dense::RowMajorMatrix;
 use p3_matrix::Matrix;
 use rand::random;
 
-use crate::columns::{Blake3Cols, NUM_BLAKE3_COLS};
+use crate::columns::{Blake3Cols, BLAKE3_COL_MAP, NUM_BLAKE3_COLS};
 use crate::constants::{permute, BITS_PER_LIMB, IV};
-use crate::{generate_trace_rows, Blake3State, FullRound, QuarterRound};
+use crate::{generate_trace_rows, Blake3State, FullRound, QuarterRound, BLAKE3_FIELD_REQUIREMENT};
 
-/// Assumes the field size is at least 16 bits.
+/// Assumes the field size is between 2^20 and 2^32, see `BLAKE3_FIELD_REQUIREMENT`.
 #[derive(Debug)]
 pub struct Blake3Air {}
 
@@ -32,6 +36,24 @@ impl Blake3Air {
         generate_trace_rows(inputs)
     }
 
+    /// Sends `(input, output)` of every row's compression to `bus`. Each 32-bit word is sent as its
+    /// `BITS_PER_LIMB`-bit limbs. The input is the 16 block words, the 8 chaining values, the low
+    /// and high counter words, the block length and the flags; the output is the 16 output words.
+    pub fn compression_interaction<F: Field + Arithmetic>(&self, bus: usize) -> Interaction<F> {
+        let map = &BLAKE3_COL_MAP;
+        let input_words = map
+            .inputs
+            .iter()
+            .chain(map.chaining_values.iter().flatten())
+            .chain([&map.counter_low, &map.counter_hi, &map.block_len, &map.flags]);
+        let output_words = map.outputs.iter().flatten();
+        let fields = input_words
+            .chain(output_words)
+            .flat_map(|bits| bits.chunks(BITS_PER_LIMB).map(pack_bits))
+            .collect();
+        Interaction::send(bus, fields, VirtualPairCol::constant(F::one()))
+    }
+
     /// Verify that the quarter round function has been correctly computed.
     ///
     /// We assume that the values in a, b, c, d have all been range checked to be
@@ -231,8 +253,14 @@ impl<F> BaseAir<F> for Blake3Air {
     fn width(&self) -> usize {
         NUM_BLAKE3_COLS
     }
+
+    fn field_requirements(&self) -> Vec<FieldRequirement> {
+        vec![BLAKE3_FIELD_REQUIREMENT]
+    }
 }
 
+impl<F: Field + Arithmetic> BaseAirWithPublicValues<F> for Blake3Air {}
+
 impl<AB: AirBuilder> Air<AB> for Blake3Air {
     #[inline]
     fn eval(&self, builder: &mut AB) {
@@ -449,3 +477,14 @@ impl<AB: AirBuilder> Air<AB> for Blake3Air {
         }
     }
 }
+
+/// The little-endian combination of the given bit columns.
+fn pack_bits<F: Field + Arithmetic>(bits: &[usize]) -> VirtualPairCol<F> {
+    VirtualPairCol::new_main(
+        bits.iter()
+            .enumerate()
+            .map(|(i, &column)| (column, F::from_u32(1 << i)))
+            .collect(),
+        F::zero(),
+    )
+}
@@ -2,14 +2,14 @@
 // Original authors: Plonky3 authors, 2022
 // Modifications by Ingonyama, 2025
 
-use core::borrow::{Borrow, BorrowMut};
-use core::mem::size_of;
+use icicle_trace::columns::{column_map, AirColumns};
 
 use crate::constants::U32_LIMBS;
 
 /// Columns for a Blake-3 AIR which computes one permutation per row.
 ///
 /// This is a pretty wide trace but that should be fine.
+#[derive(AirColumns)]
 #[repr(C)]
 pub struct Blake3Cols<T> {
     // The inputs to the hash function.
@@ -39,6 +39,7 @@ pub struct Blake3Cols<T> {
 ///
 /// Rows `0` and `2` are saved as `2` `16` bit limbs.
 /// Rows `1` and `3` are saved as `32` boolean values.
+#[derive(AirColumns)]
 #[repr(C)]
 pub struct Blake3State<T> {
     pub row0: [[T; U32_LIMBS]; 4],
@@ -48,6 +49,7 @@ pub struct Blake3State<T> {
 }
 
 /// Full round columns.
+#[derive(AirColumns)]
 #[repr(C)]
 pub struct FullRound<T> {
     // A full round of the Blake3 hash consists of 2 sub rounds each containing 4 applications
@@ -97,26 +99,5 @@ pub(crate) struct QuarterRound<'a, T, U> {
     pub d_output: &'a [T; 32],
 }
 
-pub const NUM_BLAKE3_COLS: usize = size_of::<Blake3Cols<u8>>();
-
-impl<T> Borrow<Blake3Cols<T>> for [T] {
-    fn borrow(&self) -> &Blake3Cols<T> {
-        debug_assert_eq!(self.len(), NUM_BLAKE3_COLS);
-        let (prefix, shorts, suffix) = unsafe { self.align_to::<Blake3Cols<T>>() };
-        debug_assert!(prefix.is_empty(), "Alignment should match");
-        debug_assert!(suffix.is_empty(), "Alignment should match");
-        debug_assert_eq!(shorts.len(), 1);
-        &shorts[0]
-    }
-}
-
-impl<T> BorrowMut<Blake3Cols<T>> for [T] {
-    fn borrow_mut(&mut self) -> &mut Blake3Cols<T> {
-        debug_assert_eq!(self.len(), NUM_BLAKE3_COLS);
-        let (prefix, shorts, suffix) = unsafe { self.align_to_mut::<Blake3Cols<T>>() };
-        debug_assert!(prefix.is_empty(), "Alignment should match");
-        debug_assert!(suffix.is_empty(), "Alignment should match");
-        debug_assert_eq!(shorts.len(), 1);
-        &mut shorts[0]
-    }
-}
+pub const NUM_BLAKE3_COLS: usize = <Blake3Cols<u8> as AirColumns<u8>>::NUM_COLUMNS;
+pub(crate) const BLAKE3_COL_MAP: Blake3Cols<usize> = column_map();
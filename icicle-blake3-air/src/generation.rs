@@ -9,22 +9,32 @@ use icicle_core::traits::Arithmetic;
 use icicle_core::field::Field;
 use icicle_core::bignum::BigNum;
 
-use icicle_trace::utils::u32_to_bits_le;
+use icicle_trace::utils::{u32_to_bits_le, u32_to_limbs};
+use icicle_trace::{FieldRequirementError, TraceViewMut};
 use p3_matrix::dense::RowMajorMatrix;
 use rayon::prelude::*;
 use tracing::instrument;
 
 use crate::columns::{Blake3Cols, NUM_BLAKE3_COLS};
-use crate::constants::{permute, IV};
-use crate::{Blake3State, FullRound};
+use crate::constants::{permute, BITS_PER_LIMB, IV, U32_LIMBS};
+use crate::{Blake3State, FullRound, BLAKE3_FIELD_REQUIREMENT};
 
 pub fn zero_vec<F: Field + Arithmetic>(num_elements: usize) -> Vec<F> {
     vec![F::zero(); num_elements]
 }
 
+/// Like `try_generate_trace_rows`, but panics if `F` does not satisfy `BLAKE3_FIELD_REQUIREMENT`.
+pub fn generate_trace_rows<F: Field + Arithmetic>(inputs: Vec<[u32; 24]>) -> RowMajorMatrix<F> {
+    try_generate_trace_rows(inputs).unwrap_or_else(|err| panic!("{}", err))
+}
+
 // TODO: Take generic iterable
 #[instrument(name = "generate Blake3 trace", skip_all)]
-pub fn generate_trace_rows<F: Field + Arithmetic>(inputs: Vec<[u32; 24]>) -> RowMajorMatrix<F> {
+pub fn try_generate_trace_rows<F: Field + Arithmetic>(
+    inputs: Vec<[u32; 24]>,
+) -> Result<RowMajorMatrix<F>, FieldRequirementError> {
+    BLAKE3_FIELD_REQUIREMENT.check::<F>()?;
+
     let num_rows = inputs.len();
     assert!(
         num_rows.is_power_of_two(),
@@ -32,10 +42,7 @@ pub fn generate_trace_rows<F: Field + Arithmetic>(inputs: Vec<[u32; 24]>) -> Row
     );
 
     let mut trace = RowMajorMatrix::new(zero_vec::<F>(num_rows * NUM_BLAKE3_COLS), NUM_BLAKE3_COLS);
-    let (prefix, rows, suffix) = unsafe { trace.values.align_to_mut::<Blake3Cols<F>>() };
-    assert!(prefix.is_empty(), "Alignment should match");
-    assert!(suffix.is_empty(), "Alignment should match");
-    assert_eq!(rows.len(), num_rows);
+    let mut rows = TraceViewMut::<Blake3Cols<F>>::new(&mut trace);
 
     rows.par_iter_mut()
         .zip(inputs)
@@ -44,7 +51,7 @@ pub fn generate_trace_rows<F: Field + Arithmetic>(inputs: Vec<[u32; 24]>) -> Row
             generate_trace_rows_for_perm(row, input, counter, num_rows);
         });
 
-    trace
+    Ok(trace)
 }
 
 /// Each row is one full implementation of the Blake-3 hash.
@@ -69,12 +76,8 @@ fn generate_trace_rows_for_perm<F: Field + Arithmetic>(
     // We set the flags initial value to just be 0.
     row.flags = u32_to_bits_le(0);
 
-    row.initial_row0 = array::from_fn(|i| {
-        [
-            F::from_u32(input[16 + i] as u16 as u32),
-            F::from_u32(input[16 + i] >> 16),
-        ]
-    });
+    row.initial_row0 =
+        array::from_fn(|i| u32_to_limbs::<_, BITS_PER_LIMB, U32_LIMBS>(input[16 + i]));
 
     row.initial_row2 = array::from_fn(|i| [F::from_u32(IV[i][0]), F::from_u32(IV[i][1])]);
 
@@ -85,10 +88,10 @@ fn generate_trace_rows_for_perm<F: Field + Arithmetic>(
         [input[16], input[16 + 1], input[16 + 2], input[16 + 3]],
         [input[16 + 4], input[16 + 5], input[16 + 6], input[16 + 7]],
         [
-            IV[0][0] + (IV[0][1] << 16),
-            IV[1][0] + (IV[1][1] << 16),
-            IV[2][0] + (IV[2][1] << 16),
-            IV[3][0] + (IV[3][1] << 16),
+            IV[0][0] + (IV[0][1] << BITS_PER_LIMB),
+            IV[1][0] + (IV[1][1] << BITS_PER_LIMB),
+            IV[2][0] + (IV[2][1] << BITS_PER_LIMB),
+            IV[3][0] + (IV[3][1] << BITS_PER_LIMB),
         ],
         [counter as u32, (counter >> 32) as u32, block_len as u32, 0],
     ];
@@ -229,18 +232,8 @@ fn save_state_to_trace<FA: Field + Arithmetic>(
     trace: &mut Blake3State<FA>,
     state: &[[u32; 4]; 4],
 ) {
-    trace.row0 = array::from_fn(|i| {
-        [
-            FA::from_u32(state[0][i] as u16 as u32), // Store the bottom 16 bits packed.
-            FA::from_u32(state[0][i] >> 16),         // Store the top 16 bits packed.
-        ]
-    });
+    trace.row0 = array::from_fn(|i| u32_to_limbs::<_, BITS_PER_LIMB, U32_LIMBS>(state[0][i])); // Store the limbs packed.
     trace.row1 = array::from_fn(|i| u32_to_bits_le(state[1][i])); // Store all 32 bits unpacked.
-    trace.row2 = array::from_fn(|i| {
-        [
-            FA::from_u32(state[2][i] as u16 as u32), // Store the bottom 16 bits packed.
-            FA::from_u32(state[2][i] >> 16),         // Store the top 16 bits packed.
-        ]
-    });
+    trace.row2 = array::from_fn(|i| u32_to_limbs::<_, BITS_PER_LIMB, U32_LIMBS>(state[2][i])); // Store the limbs packed.
     trace.row3 = array::from_fn(|i| u32_to_bits_le(state[3][i])); // Store all 32 bits unpacked.
 }
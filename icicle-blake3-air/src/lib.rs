@@ -8,6 +8,8 @@
 
 extern crate alloc;
 
+use icicle_trace::FieldRequirement;
+
 mod air;
 mod columns;
 mod constants;
@@ -16,3 +18,7 @@ mod generation;
 pub use air::*;
 pub use columns::*;
 pub use generation::*;
+
+/// The field sizes the Blake-3 AIR is sound over.
+pub const BLAKE3_FIELD_REQUIREMENT: FieldRequirement =
+    FieldRequirement::between("blake3", 1 << 20, 1 << 32);
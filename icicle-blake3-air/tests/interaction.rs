@@ -0,0 +1,177 @@
+use icicle_babybear::field::ScalarField as Fr;
+use icicle_blake3_air::{generate_trace_rows, Blake3Air, NUM_BLAKE3_COLS};
+use icicle_core::bignum::BigNum;
+use icicle_trace::utils::u32_to_limbs;
+use icicle_trace::{
+    check_bus_balance, check_constraints, BaseAir, BaseAirWithPublicValues, BusInstance,
+    Interaction, InteractionAir, VirtualPairCol,
+};
+use p3_matrix::dense::RowMajorMatrix;
+
+const BUS: usize = 5;
+
+/// The number of 16-bit halves `compression_interaction` sends: 28 input words and 16 output words.
+const TUPLE_WIDTH: usize = 2 * (28 + 16);
+
+const IV: [u32; 8] = [
+    0x6A09E667, 0xBB67AE85, 0x3C6EF372, 0xA54FF53A, 0x510E527F, 0x9B05688C, 0x1F83D9AB, 0x5BE0CD19,
+];
+
+const MSG_PERMUTATION: [usize; 16] = [2, 6, 3, 10, 7, 0, 4, 13, 1, 11, 12, 5, 9, 14, 15, 8];
+
+/// Sends the compressions of a `Blake3Air` trace.
+pub struct Blake3Sender {}
+
+/// Receives `(input, compress(input))` once per row, as the halves `compression_interaction`
+/// sends.
+pub struct CompressionRequests {}
+
+impl BaseAir<Fr> for Blake3Sender {
+    fn width(&self) -> usize {
+        NUM_BLAKE3_COLS
+    }
+}
+
+impl BaseAir<Fr> for CompressionRequests {
+    fn width(&self) -> usize {
+        TUPLE_WIDTH
+    }
+}
+
+impl BaseAirWithPublicValues<Fr> for Blake3Sender {}
+impl BaseAirWithPublicValues<Fr> for CompressionRequests {}
+
+impl InteractionAir<Fr> for Blake3Sender {
+    fn interactions(&self) -> Vec<Interaction<Fr>> {
+        vec![Blake3Air {}.compression_interaction(BUS)]
+    }
+}
+
+impl InteractionAir<Fr> for CompressionRequests {
+    fn interactions(&self) -> Vec<Interaction<Fr>> {
+        let fields = (0..TUPLE_WIDTH).map(VirtualPairCol::single_main).collect();
+        vec![Interaction::receive(
+            BUS,
+            fields,
+            VirtualPairCol::constant(Fr::one()),
+        )]
+    }
+}
+
+fn g(state: &mut [u32; 16], [a, b, c, d]: [usize; 4], mx: u32, my: u32) {
+    state[a] = state[a].wrapping_add(state[b]).wrapping_add(mx);
+    state[d] = (state[d] ^ state[a]).rotate_right(16);
+    state[c] = state[c].wrapping_add(state[d]);
+    state[b] = (state[b] ^ state[c]).rotate_right(12);
+    state[a] = state[a].wrapping_add(state[b]).wrapping_add(my);
+    state[d] = (state[d] ^ state[a]).rotate_right(8);
+    state[c] = state[c].wrapping_add(state[d]);
+    state[b] = (state[b] ^ state[c]).rotate_right(7);
+}
+
+/// The Blake-3 compression function, as in the reference implementation.
+fn compress(cv: [u32; 8], block: [u32; 16], counter: u64, block_len: u32, flags: u32) -> [u32; 16] {
+    let mut state = [
+        cv[0],
+        cv[1],
+        cv[2],
+        cv[3],
+        cv[4],
+        cv[5],
+        cv[6],
+        cv[7],
+        IV[0],
+        IV[1],
+        IV[2],
+        IV[3],
+        counter as u32,
+        (counter >> 32) as u32,
+        block_len,
+        flags,
+    ];
+    let mut m = block;
+    for round in 0..7 {
+        g(&mut state, [0, 4, 8, 12], m[0], m[1]);
+        g(&mut state, [1, 5, 9, 13], m[2], m[3]);
+        g(&mut state, [2, 6, 10, 14], m[4], m[5]);
+        g(&mut state, [3, 7, 11, 15], m[6], m[7]);
+        g(&mut state, [0, 5, 10, 15], m[8], m[9]);
+        g(&mut state, [1, 6, 11, 12], m[10], m[11]);
+        g(&mut state, [2, 7, 8, 13], m[12], m[13]);
+        g(&mut state, [3, 4, 9, 14], m[14], m[15]);
+        if round < 6 {
+            m = core::array::from_fn(|i| m[MSG_PERMUTATION[i]]);
+        }
+    }
+    for i in 0..8 {
+        state[i] ^= state[i + 8];
+        state[i + 8] ^= cv[i];
+    }
+    state
+}
+
+/// Four inputs, each 16 block words followed by 8 chaining value words.
+fn inputs() -> Vec<[u32; 24]> {
+    (1..=4u32)
+        .map(|i| core::array::from_fn(|j| i.wrapping_mul(0x9E37_79B9) ^ j as u32))
+        .collect()
+}
+
+/// The trace generator compresses row `i` with counter `i`, the number of rows as the block length
+/// and no flags.
+fn requests(inputs: &[[u32; 24]]) -> RowMajorMatrix<Fr> {
+    let block_len = inputs.len() as u32;
+    let values = inputs
+        .iter()
+        .enumerate()
+        .flat_map(|(counter, input)| {
+            let block: [u32; 16] = core::array::from_fn(|i| input[i]);
+            let cv: [u32; 8] = core::array::from_fn(|i| input[16 + i]);
+            let counter = counter as u64;
+            let output = compress(cv, block, counter, block_len, 0);
+            input
+                .iter()
+                .copied()
+                .chain([counter as u32, (counter >> 32) as u32, block_len, 0])
+                .chain(output)
+                .flat_map(u32_to_limbs::<Fr, 16, 2>)
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    RowMajorMatrix::new(values, TUPLE_WIDTH)
+}
+
+#[test]
+fn compressions_balance_against_the_requests() {
+    let inputs = inputs();
+    let main = generate_trace_rows::<Fr>(inputs.clone());
+    assert_eq!(check_constraints(&Blake3Air {}, &main, &[]), Ok(()));
+
+    let requests = requests(&inputs);
+    let instances = [
+        BusInstance::new(&Blake3Sender {}, &main, &[]),
+        BusInstance::new(&CompressionRequests {}, &requests, &[]),
+    ];
+    check_bus_balance(&instances).unwrap();
+}
+
+#[test]
+fn wrong_output_is_unbalanced() {
+    let inputs = inputs();
+    let main = generate_trace_rows::<Fr>(inputs.clone());
+    let mut requests = requests(&inputs);
+    // The low half of the last output word of the third request.
+    let index = 3 * TUPLE_WIDTH - 2;
+    requests.values[index] = requests.values[index] + Fr::one();
+
+    let instances = [
+        BusInstance::new(&Blake3Sender {}, &main, &[]),
+        BusInstance::new(&CompressionRequests {}, &requests, &[]),
+    ];
+    let error = check_bus_balance(&instances).unwrap_err();
+    assert_eq!(error.imbalances.len(), 2);
+    assert!(error
+        .imbalances
+        .iter()
+        .all(|imbalance| imbalance.bus == BUS));
+}
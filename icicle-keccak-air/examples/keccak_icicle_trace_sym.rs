@@ -10,7 +10,7 @@ fn test_trace(n: usize) {
     let trace = keccak_air.generate_trace_rows::<Fr>(n, 0);
   
 
-    let symbolic_constraints = get_symbolic_constraints::<Fr, KeccakAir>(&KeccakAir {}, 0, 0);
+    let symbolic_constraints = get_symbolic_constraints::<Fr, KeccakAir>(&KeccakAir {});
     let constraint_degree = symbolic_constraints
     .iter()
     .map(SymbolicExpression::degree_multiple)
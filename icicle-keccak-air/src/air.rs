@@ -2,24 +2,28 @@
 // Original authors: Plonky3 authors, 2022
 // Modifications by Ingonyama, 2025
 
+use alloc::vec;
 use alloc::vec::Vec;
 use core::borrow::Borrow;
 
 use icicle_core::traits::Arithmetic;
 use icicle_core::field::Field;
 use icicle_core::bignum::BigNum;
-use icicle_trace::{Air, AirBuilder, BaseAir};
+use icicle_trace::{
+    Air, AirBuilder, BaseAir, BaseAirWithPublicValues, FieldRequirement, Interaction,
+    VirtualPairCol,
+};
 
 use p3_matrix::dense::RowMajorMatrix;
 use p3_matrix::Matrix;
 use rand::random;
 
-use crate::columns::{KeccakCols, NUM_KECCAK_COLS};
+use crate::columns::{KeccakCols, KECCAK_COL_MAP, NUM_KECCAK_COLS};
 use crate::constants::rc_value_bit;
 use crate::round_flags::eval_round_flags;
-use crate::{generate_trace_rows, BITS_PER_LIMB, NUM_ROUNDS, U64_LIMBS};
+use crate::{generate_trace_rows, BITS_PER_LIMB, KECCAK_FIELD_REQUIREMENT, NUM_ROUNDS, U64_LIMBS};
 
-/// Assumes the field size is at least 16 bits.
+/// Assumes the field size is between 2^16 and 2^32, see `KECCAK_FIELD_REQUIREMENT`.
 #[derive(Debug)]
 pub struct KeccakAir {}
 
@@ -32,18 +36,53 @@ impl KeccakAir {
         let inputs = (0..num_hashes).map(|_| random()).collect::<Vec<_>>();
         generate_trace_rows(inputs, extra_capacity_bits)
     }
+
+    /// Sends `(preimage, output)` of every exported permutation to `bus`, as the 100 16-bit limbs
+    /// of the input state followed by the 100 limbs of the output state, both in y-major order.
+    pub fn permutation_interaction<F: Field + Arithmetic>(&self, bus: usize) -> Interaction<F> {
+        let mut fields = Vec::with_capacity(2 * 25 * U64_LIMBS);
+        for y in 0..5 {
+            for x in 0..5 {
+                for limb in 0..U64_LIMBS {
+                    fields.push(VirtualPairCol::single_main(
+                        KECCAK_COL_MAP.preimage[y][x][limb],
+                    ));
+                }
+            }
+        }
+        for y in 0..5 {
+            for x in 0..5 {
+                for limb in 0..U64_LIMBS {
+                    fields.push(VirtualPairCol::single_main(
+                        KECCAK_COL_MAP.a_prime_prime_prime(y, x, limb),
+                    ));
+                }
+            }
+        }
+        Interaction::send(
+            bus,
+            fields,
+            VirtualPairCol::single_main(KECCAK_COL_MAP.export),
+        )
+    }
 }
 
 impl<F> BaseAir<F> for KeccakAir {
     fn width(&self) -> usize {
         NUM_KECCAK_COLS
     }
+
+    fn field_requirements(&self) -> Vec<FieldRequirement> {
+        vec![KECCAK_FIELD_REQUIREMENT]
+    }
 }
 
+impl<F: Field + Arithmetic> BaseAirWithPublicValues<F> for KeccakAir {}
+
 impl<AB: AirBuilder> Air<AB> for KeccakAir {
     #[inline]
     fn eval(&self, builder: &mut AB) {
-        eval_round_flags(builder);
+        builder.scope("keccak.round_flags", |builder| eval_round_flags(builder));
 
         let main = builder.main();
         let local_option = main.row_slice(0);
@@ -57,6 +96,8 @@ impl<AB: AirBuilder> Air<AB> for KeccakAir {
         let final_step = local.step_flags[NUM_ROUNDS - 1];
         let not_final_step = builder.one() - final_step.into();
 
+        builder.push_scope("keccak.input");
+
         // If this is the first step, the input A must match the preimage.
         for y in 0..5 {
             for x in 0..5 {
@@ -91,6 +132,9 @@ impl<AB: AirBuilder> Air<AB> for KeccakAir {
             .when(not_final_step.clone())
             .assert_zero(local.export);
 
+        builder.pop_scope();
+        builder.push_scope("keccak.theta");
+
         // C'[x, z] = xor(C[x, z], C[x - 1, z], C[x + 1, z - 1]).
         // Note that if all entries of C are boolean, the arithmetic generalization
         // xor3 function only outputs 0, 1 and so this check also ensures that all
@@ -157,6 +201,9 @@ impl<AB: AirBuilder> Air<AB> for KeccakAir {
             }
         }
 
+        builder.pop_scope();
+        builder.push_scope("keccak.rho_pi_chi");
+
         // A''[x, y] = xor(B[x, y], andn(B[x + 1, y], B[x + 2, y])).
         // As B is a rotation of A', all entries must be bools and so
         // this check also range checks A''.
@@ -180,6 +227,9 @@ impl<AB: AirBuilder> Air<AB> for KeccakAir {
             }
         }
 
+        builder.pop_scope();
+        builder.push_scope("keccak.iota");
+
         // Pre-check booleans for A''[0, 0] bits
         for z in 0..64 {
             builder.assert_bool(local.a_prime_prime_0_0_bits[z]);
@@ -220,6 +270,9 @@ impl<AB: AirBuilder> Air<AB> for KeccakAir {
             );
         }
 
+        builder.pop_scope();
+        builder.push_scope("keccak.output");
+
         // Enforce that this round's output equals the next round's input.
         for x in 0..5 {
             for y in 0..5 {
@@ -232,5 +285,7 @@ impl<AB: AirBuilder> Air<AB> for KeccakAir {
                 }
             }
         }
+
+        builder.pop_scope();
     }
 }
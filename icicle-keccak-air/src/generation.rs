@@ -11,19 +11,30 @@ use core::mem::transmute;
 use icicle_core::traits::Arithmetic;
 use icicle_core::field::Field;
 use icicle_core::bignum::BigNum;
-use icicle_trace::utils::{u64_to_16_bit_limbs, u64_to_bits_le};
+use icicle_trace::utils::{u64_to_bits_le, u64_to_limbs};
+use icicle_trace::{FieldRequirementError, TraceViewMut};
 use p3_matrix::dense::RowMajorMatrix;
 use tracing::instrument;
 
 use crate::columns::{KeccakCols, NUM_KECCAK_COLS};
-use crate::{NUM_ROUNDS, R, RC, U64_LIMBS};
+use crate::{BITS_PER_LIMB, KECCAK_FIELD_REQUIREMENT, NUM_ROUNDS, R, RC, U64_LIMBS};
 
-// TODO: Take generic iterable
-#[instrument(name = "generate Keccak trace", skip_all)]
+/// Like `try_generate_trace_rows`, but panics if `F` does not satisfy `KECCAK_FIELD_REQUIREMENT`.
 pub fn generate_trace_rows<F: Field + Arithmetic>(
     inputs: Vec<[u64; 25]>,
     extra_capacity_bits: usize,
 ) -> RowMajorMatrix<F> {
+    try_generate_trace_rows(inputs, extra_capacity_bits).unwrap_or_else(|err| panic!("{}", err))
+}
+
+// TODO: Take generic iterable
+#[instrument(name = "generate Keccak trace", skip_all)]
+pub fn try_generate_trace_rows<F: Field + Arithmetic>(
+    inputs: Vec<[u64; 25]>,
+    extra_capacity_bits: usize,
+) -> Result<RowMajorMatrix<F>, FieldRequirementError> {
+    KECCAK_FIELD_REQUIREMENT.check::<F>()?;
+
     let num_rows = (inputs.len() * NUM_ROUNDS).next_power_of_two();
     let trace_length = num_rows * NUM_KECCAK_COLS;
 
@@ -32,23 +43,26 @@ pub fn generate_trace_rows<F: Field + Arithmetic>(
     long_trace.truncate(trace_length);
 
     let mut trace = RowMajorMatrix::new(long_trace, NUM_KECCAK_COLS);
-    let (prefix, rows, suffix) = unsafe { trace.values.align_to_mut::<KeccakCols<F>>() };
-    assert!(prefix.is_empty(), "Alignment should match");
-    assert!(suffix.is_empty(), "Alignment should match");
-    assert_eq!(rows.len(), num_rows);
+    let mut rows = TraceViewMut::<KeccakCols<F>>::new(&mut trace);
 
-    let num_padding_inputs = num_rows.div_ceil(NUM_ROUNDS) - inputs.len();
+    let num_hashes = inputs.len();
+    let num_padding_inputs = num_rows.div_ceil(NUM_ROUNDS) - num_hashes;
     let padded_inputs = inputs
         .into_iter()
         .chain(repeat([0; 25]).take(num_padding_inputs));
 
     rows.chunks_mut(NUM_ROUNDS)
         .zip(padded_inputs)
-        .for_each(|(row, input)| {
+        .enumerate()
+        .for_each(|(i, (row, input))| {
             generate_trace_rows_for_perm(row, input);
+            // Only the final step of a real (non-padding) permutation is exported.
+            if i < num_hashes {
+                row[NUM_ROUNDS - 1].export = F::one();
+            }
         });
 
-    trace
+    Ok(trace)
 }
 
 /// `rows` will normally consist of 24 rows, with an exception for the final row.
@@ -59,7 +73,7 @@ fn generate_trace_rows_for_perm<F: Field + Arithmetic>(
     let mut current_state: [[u64; 5]; 5] = unsafe { transmute(input) };
 
     let initial_state: [[[F; 4]; 5]; 5] =
-        array::from_fn(|y| array::from_fn(|x| u64_to_16_bit_limbs(current_state[x][y])));
+        array::from_fn(|y| array::from_fn(|x| u64_to_limbs::<_, BITS_PER_LIMB, U64_LIMBS>(current_state[x][y])));
 
     // Populate the round input for the first round.
     rows[0].a = initial_state.clone();
@@ -133,7 +147,7 @@ fn generate_trace_row_for_round<F: Field + Arithmetic>(
     });
     for (x, x_row) in current_state.iter().enumerate() {
         for (y, elem) in x_row.iter().enumerate() {
-            row.a_prime_prime[y][x] = u64_to_16_bit_limbs(*elem);
+            row.a_prime_prime[y][x] = u64_to_limbs::<_, BITS_PER_LIMB, U64_LIMBS>(*elem);
         }
     }
 
@@ -142,5 +156,5 @@ fn generate_trace_row_for_round<F: Field + Arithmetic>(
     // A''[0, 0] is additionally xor'd with RC.
     current_state[0][0] ^= RC[round];
 
-    row.a_prime_prime_prime_0_0_limbs = u64_to_16_bit_limbs(current_state[0][0]);
+    row.a_prime_prime_prime_0_0_limbs = u64_to_limbs::<_, BITS_PER_LIMB, U64_LIMBS>(current_state[0][0]);
 }
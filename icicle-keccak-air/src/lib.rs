@@ -8,6 +8,8 @@
 
 extern crate alloc;
 
+use icicle_trace::FieldRequirement;
+
 mod air;
 mod columns;
 mod constants;
@@ -24,3 +26,7 @@ const BITS_PER_LIMB: usize = 16;
 pub const U64_LIMBS: usize = 64 / BITS_PER_LIMB;
 const RATE_BITS: usize = 1088;
 const RATE_LIMBS: usize = RATE_BITS / BITS_PER_LIMB;
+
+/// The field sizes the Keccak AIR is sound over.
+pub const KECCAK_FIELD_REQUIREMENT: FieldRequirement =
+    FieldRequirement::between("keccak", 1 << 16, 1 << 32);
@@ -0,0 +1,117 @@
+use icicle_babybear::field::ScalarField as Fr;
+use icicle_core::bignum::BigNum;
+use icicle_keccak_air::{generate_trace_rows, KeccakAir, NUM_KECCAK_COLS};
+use icicle_trace::utils::u64_to_limbs;
+use icicle_trace::{
+    check_bus_balance, check_constraints, BaseAir, BaseAirWithPublicValues, BusInstance,
+    Interaction, InteractionAir, VirtualPairCol,
+};
+use p3_matrix::dense::RowMajorMatrix;
+
+const BUS: usize = 3;
+
+/// Sends the permutations of a `KeccakAir` trace.
+pub struct KeccakSender {}
+
+/// Receives `(state, keccak_f(state))` once per row, as the 200 limbs `permutation_interaction`
+/// sends.
+pub struct PermutationRequests {}
+
+impl BaseAir<Fr> for KeccakSender {
+    fn width(&self) -> usize {
+        NUM_KECCAK_COLS
+    }
+}
+
+impl BaseAir<Fr> for PermutationRequests {
+    fn width(&self) -> usize {
+        2 * 25 * 4
+    }
+}
+
+impl BaseAirWithPublicValues<Fr> for KeccakSender {}
+impl BaseAirWithPublicValues<Fr> for PermutationRequests {}
+
+impl InteractionAir<Fr> for KeccakSender {
+    fn interactions(&self) -> Vec<Interaction<Fr>> {
+        vec![KeccakAir {}.permutation_interaction(BUS)]
+    }
+}
+
+impl InteractionAir<Fr> for PermutationRequests {
+    fn interactions(&self) -> Vec<Interaction<Fr>> {
+        let fields = (0..self.width()).map(VirtualPairCol::single_main).collect();
+        vec![Interaction::receive(
+            BUS,
+            fields,
+            VirtualPairCol::constant(Fr::one()),
+        )]
+    }
+}
+
+/// Lanes `5 * y + x` of three states, as `keccak::f1600` indexes them.
+fn states() -> Vec<[u64; 25]> {
+    (1..=3u64)
+        .map(|i| core::array::from_fn(|lane| (i * 0x0123_4567_89AB_CDEF) ^ lane as u64))
+        .collect()
+}
+
+/// The trace generator takes its states indexed by `5 * x + y` instead.
+fn trace(states: &[[u64; 25]]) -> RowMajorMatrix<Fr> {
+    let inputs = states
+        .iter()
+        .map(|state| core::array::from_fn(|i| state[5 * (i % 5) + i / 5]))
+        .collect();
+    generate_trace_rows(inputs, 0)
+}
+
+fn requests(states: &[[u64; 25]]) -> RowMajorMatrix<Fr> {
+    let values = states
+        .iter()
+        .flat_map(|state| {
+            let mut output = *state;
+            keccak::f1600(&mut output);
+            state
+                .iter()
+                .chain(output.iter())
+                .flat_map(|&lane| u64_to_limbs::<Fr, 16, 4>(lane))
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    RowMajorMatrix::new(values, PermutationRequests {}.width())
+}
+
+#[test]
+fn exported_permutations_balance_against_the_requests() {
+    let states = states();
+    // Three permutations fill 72 of the 128 rows; the padding permutations must not be sent.
+    let main = trace(&states);
+    assert_eq!(check_constraints(&KeccakAir {}, &main, &[]), Ok(()));
+
+    let requests = requests(&states);
+    let instances = [
+        BusInstance::new(&KeccakSender {}, &main, &[]),
+        BusInstance::new(&PermutationRequests {}, &requests, &[]),
+    ];
+    check_bus_balance(&instances).unwrap();
+}
+
+#[test]
+fn wrong_output_is_unbalanced() {
+    let states = states();
+    let main = trace(&states);
+    let mut requests = requests(&states);
+    // The first limb of the second request's output.
+    requests.values[200 + 100] = requests.values[200 + 100] + Fr::one();
+
+    let instances = [
+        BusInstance::new(&KeccakSender {}, &main, &[]),
+        BusInstance::new(&PermutationRequests {}, &requests, &[]),
+    ];
+    let error = check_bus_balance(&instances).unwrap_err();
+    assert_eq!(error.imbalances.len(), 2);
+    assert!(error
+        .imbalances
+        .iter()
+        .all(|imbalance| imbalance.bus == BUS));
+}
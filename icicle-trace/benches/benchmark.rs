@@ -6,13 +6,15 @@ use icicle_core::{
 };
 use icicle_runtime::Device;
 use icicle_babybear::field::ScalarField as Fr;
+use icicle_trace::columns::AirColumns;
+use icicle_trace::trace_view::TraceViewMut;
 //constraints
 
 
 use p3_matrix::dense::RowMajorMatrix;
 
 const SAMPLES:usize = 32768;
-const NUM_FIBONACCI_COLS: usize = 2;
+const NUM_FIBONACCI_COLS: usize = <FibonacciRow<u8> as AirColumns<u8>>::NUM_COLUMNS;
 
 pub fn set_backend_cpu() {
     
@@ -31,6 +33,8 @@ pub fn try_load_and_set_backend_gpu() {
         set_backend_cpu();
 }
 }
+#[derive(AirColumns)]
+#[repr(C)]
 pub struct FibonacciRow<F> {
     pub left: F,
     pub right: F,
@@ -49,10 +53,7 @@ pub fn generate_trace_rows<F: Field + Arithmetic>(
     let mut trace =
         RowMajorMatrix::new(vec![F::zero(); n * NUM_FIBONACCI_COLS], NUM_FIBONACCI_COLS);
 
-    let (prefix, rows, suffix) = unsafe { trace.values.align_to_mut::<FibonacciRow<F>>() };
-    assert!(prefix.is_empty(), "Alignment should match");
-    assert!(suffix.is_empty(), "Alignment should match");
-    assert_eq!(rows.len(), n);
+    let mut rows = TraceViewMut::<FibonacciRow<F>>::new(&mut trace);
 
     rows[0] = FibonacciRow::new(F::from_u32(a), F::from_u32(b));
 
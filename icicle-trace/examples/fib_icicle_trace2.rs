@@ -1,10 +1,10 @@
-use std::borrow::Borrow;
-
 use icicle_core::traits::Arithmetic;
 use icicle_core::field::Field;
 use icicle_core::bignum::BigNum;
 
 use icicle_babybear::field::ScalarField as Fr;
+use icicle_trace::columns::AirColumns;
+use icicle_trace::trace_view::TraceViewMut;
 //constraints
 use p3_air::BaseAir;
 use p3_matrix::dense::RowMajorMatrix;
@@ -28,10 +28,7 @@ pub fn generate_trace_rows<F: Field + Arithmetic>(
     let mut trace =
         RowMajorMatrix::new(vec![F::zero(); n * NUM_FIBONACCI_COLS], NUM_FIBONACCI_COLS);
 
-    let (prefix, rows, suffix) = unsafe { trace.values.align_to_mut::<FibonacciRow<F>>() };
-    assert!(prefix.is_empty(), "Alignment should match");
-    assert!(suffix.is_empty(), "Alignment should match");
-    assert_eq!(rows.len(), n);
+    let mut rows = TraceViewMut::<FibonacciRow<F>>::new(&mut trace);
 
     rows[0] = FibonacciRow::new(F::from_u32(a), F::from_u32(b));
 
@@ -42,8 +39,10 @@ pub fn generate_trace_rows<F: Field + Arithmetic>(
     trace
 }
 
-const NUM_FIBONACCI_COLS: usize = 2;
+const NUM_FIBONACCI_COLS: usize = <FibonacciRow<Fr> as AirColumns<Fr>>::NUM_COLUMNS;
 
+#[derive(AirColumns)]
+#[repr(C)]
 pub struct FibonacciRow<F: Field + Arithmetic> {
     pub left: F,
     pub right: F,
@@ -54,16 +53,6 @@ impl<F: Field + Arithmetic> FibonacciRow<F> {
         FibonacciRow { left, right }
     }
 }
-impl<F: Field + Arithmetic> Borrow<FibonacciRow<F>> for [F] {
-    fn borrow(&self) -> &FibonacciRow<F> {
-        debug_assert_eq!(self.len(), NUM_FIBONACCI_COLS);
-        let (prefix, shorts, suffix) = unsafe { self.align_to::<FibonacciRow<F>>() };
-        debug_assert!(prefix.is_empty(), "Alignment should match");
-        debug_assert!(suffix.is_empty(), "Alignment should match");
-        debug_assert_eq!(shorts.len(), 1);
-        &shorts[0]
-    }
-}
 
 fn main() {
     fn test_trace(n: usize, _x: u32) {
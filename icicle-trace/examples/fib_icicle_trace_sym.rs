@@ -5,6 +5,8 @@ use icicle_core::field::Field;
 use icicle_core::bignum::BigNum;
 
 use icicle_babybear::field::ScalarField as Fr;
+use icicle_trace::columns::AirColumns;
+use icicle_trace::trace_view::TraceViewMut;
 //constraints
 
 use icicle_trace::{air::*, symbolic_builder::*, symbolic_expression::*};
@@ -22,6 +24,12 @@ impl<F: Field> BaseAir<F> for FibonacciAir {
     }
 }
 
+impl<F: Field + Arithmetic> BaseAirWithPublicValues<F> for FibonacciAir {
+    fn num_public_values(&self) -> usize {
+        3
+    }
+}
+
 impl<AB: AirBuilderWithPublicValues> Air<AB> for FibonacciAir {
     fn eval(&self, builder: &mut AB) {
         let main = builder.main();
@@ -64,10 +72,7 @@ pub fn generate_trace_rows<F: Field + Arithmetic>(
     let mut trace =
         RowMajorMatrix::new(vec![F::zero(); n * NUM_FIBONACCI_COLS], NUM_FIBONACCI_COLS);
 
-    let (prefix, rows, suffix) = unsafe { trace.values.align_to_mut::<FibonacciRow<F>>() };
-    assert!(prefix.is_empty(), "Alignment should match");
-    assert!(suffix.is_empty(), "Alignment should match");
-    assert_eq!(rows.len(), n);
+    let mut rows = TraceViewMut::<FibonacciRow<F>>::new(&mut trace);
 
     rows[0] = FibonacciRow::new(F::from_u32(a), F::from_u32(b));
 
@@ -78,8 +83,10 @@ pub fn generate_trace_rows<F: Field + Arithmetic>(
     trace
 }
 
-const NUM_FIBONACCI_COLS: usize = 2;
+const NUM_FIBONACCI_COLS: usize = <FibonacciRow<u8> as AirColumns<u8>>::NUM_COLUMNS;
 
+#[derive(AirColumns)]
+#[repr(C)]
 pub struct FibonacciRow<F> {
     pub left: F,
     pub right: F,
@@ -90,26 +97,20 @@ impl<F> FibonacciRow<F> {
         FibonacciRow { left, right }
     }
 }
-impl<F> Borrow<FibonacciRow<F>> for [F] {
-    fn borrow(&self) -> &FibonacciRow<F> {
-        debug_assert_eq!(self.len(), NUM_FIBONACCI_COLS);
-        let (prefix, shorts, suffix) = unsafe { self.align_to::<FibonacciRow<F>>() };
-        debug_assert!(prefix.is_empty(), "Alignment should match");
-        debug_assert!(suffix.is_empty(), "Alignment should match");
-        debug_assert_eq!(shorts.len(), 1);
-        &shorts[0]
-    }
-}
 
 fn test_trace(n: usize, x: u32) {
     //generate trace
     let trace = generate_trace_rows::<Fr>(0, 1, n);
     //input public values
 
-    let pis = vec![Fr::from_u32(0), Fr::from_u32(1), Fr::from_u32(x)];
+    let pis = [Fr::from_u32(0), Fr::from_u32(1), Fr::from_u32(x)];
     println!("Trace {:#?}", trace);
-    let symbolic_constraints =
-        get_symbolic_constraints::<Fr, FibonacciAir>(&FibonacciAir {}, 0, pis.len());
+    // The AIR reports how many public values it takes.
+    assert_eq!(
+        pis.len(),
+        BaseAirWithPublicValues::<Fr>::num_public_values(&FibonacciAir {})
+    );
+    let symbolic_constraints = get_symbolic_constraints::<Fr, FibonacciAir>(&FibonacciAir {});
     println!("symbolic constraints {:#?}", symbolic_constraints);
 
     let constraint_degree = symbolic_constraints
@@ -2,6 +2,7 @@
 // Original authors: Plonky3 authors, 2022
 // Modifications by Ingonyama, 2025
 
+use alloc::vec::Vec;
 use core::ops::{Add, Mul, Sub};
 
 use icicle_core::bignum;
@@ -10,14 +11,52 @@ use icicle_core::{field::Field,bignum::BigNum};
 use p3_matrix::dense::RowMajorMatrix;
 use p3_matrix::Matrix;
 
+use crate::extension::ExtensionOf;
+use crate::field_requirement::FieldRequirement;
+
 /// An AIR (algebraic intermediate representation).
 pub trait BaseAir<F>: Sync {
     /// The number of columns (a.k.a. registers) in this AIR.
     fn width(&self) -> usize;
 
+    /// The number of consecutive rows (local, next, ...) that each constraint may reference.
+    fn window_size(&self) -> usize {
+        2
+    }
+
     fn preprocessed_trace(&self) -> Option<RowMajorMatrix<F>> {
         None
     }
+
+    /// The number of preprocessed columns. AIRs with a preprocessed trace should override this
+    /// to avoid generating the trace just to measure it.
+    fn preprocessed_width(&self) -> usize {
+        self.preprocessed_trace().map_or(0, |trace| trace.width)
+    }
+
+    /// The number of columns in the auxiliary (permutation) trace.
+    fn permutation_width(&self) -> usize {
+        0
+    }
+
+    /// The number of verifier challenges the auxiliary trace depends on.
+    fn num_challenges(&self) -> usize {
+        0
+    }
+
+    /// Bounds on the field characteristic that this AIR's soundness relies on. Requirements of
+    /// gadgets used in `eval` are declared separately, through `AirBuilder::require_field`.
+    fn field_requirements(&self) -> Vec<FieldRequirement> {
+        Vec::new()
+    }
+
+    /// Columns known to the verifier whose values repeat with a power-of-two period.
+    ///
+    /// Each entry holds the values of one column over a single period, so row `i` reads
+    /// `column[i % column.len()]`.
+    fn periodic_columns(&self) -> Vec<Vec<F>> {
+        Vec::new()
+    }
 }
 
 ///  An AIR with 0 or more public values.
@@ -76,6 +115,18 @@ pub trait AirBuilder: Sized {
     }
     fn is_transition_window(&self, size: usize) -> Self::Expr;
 
+    /// Nonzero only on row `row`.
+    fn is_row(&self, row: usize) -> Self::Expr;
+
+    /// Nonzero only on rows `i` with `i % period == residue`. `period` must be a power of two
+    /// dividing the trace height.
+    fn is_row_mod(&self, residue: usize, period: usize) -> Self::Expr;
+
+    /// Nonzero only on the first row of each consecutive segment of `len` rows.
+    fn is_segment_start(&self, len: usize) -> Self::Expr {
+        self.is_row_mod(0, len)
+    }
+
     /// Returns a sub-builder whose constraints are enforced only when `condition` is nonzero.
     fn when<I: Into<Self::Expr>>(&mut self, condition: I) -> FilteredAirBuilder<'_, Self> {
         FilteredAirBuilder {
@@ -115,6 +166,23 @@ pub trait AirBuilder: Sized {
         self.when(self.is_transition_window(size))
     }
 
+    /// Returns a sub-builder whose constraints are enforced only on row `row`.
+    fn when_row(&mut self, row: usize) -> FilteredAirBuilder<'_, Self> {
+        self.when(self.is_row(row))
+    }
+
+    /// Returns a sub-builder whose constraints are enforced only on rows `i` with
+    /// `i % period == residue`.
+    fn when_row_mod(&mut self, residue: usize, period: usize) -> FilteredAirBuilder<'_, Self> {
+        self.when(self.is_row_mod(residue, period))
+    }
+
+    /// Returns a sub-builder whose constraints are enforced only on the first row of each segment
+    /// of `len` rows.
+    fn when_segment_start(&mut self, len: usize) -> FilteredAirBuilder<'_, Self> {
+        self.when(self.is_segment_start(len))
+    }
+
     /// Get a constant expression representing zero.
     fn zero(&self) -> Self::Expr;
     /// Get a constant expression representing one.
@@ -126,23 +194,49 @@ pub trait AirBuilder: Sized {
     /// Get a constant expression from a u32.
     fn from_u32(&self, val: u32) -> Self::Expr;
 
+    /// Declares that the constraints being built are only sound over fields satisfying
+    /// `requirement`. Builders which know the field check it; others ignore it.
+    #[track_caller]
+    fn require_field(&mut self, _requirement: FieldRequirement) {}
+
+    /// Enters a named constraint scope. Builders which report on individual constraints use the
+    /// stack of open scopes to name them; others ignore it.
+    fn push_scope(&mut self, _name: &str) {}
+
+    /// Leaves the innermost constraint scope.
+    fn pop_scope(&mut self) {}
+
+    /// Runs `f` inside the named constraint scope `name`, e.g.
+    /// `builder.scope("keccak.theta", |builder| ...)`. Scopes nest.
+    fn scope<R>(&mut self, name: &str, f: impl FnOnce(&mut Self) -> R) -> R {
+        self.push_scope(name);
+        let result = f(self);
+        self.pop_scope();
+        result
+    }
+
+    #[track_caller]
     fn assert_zero<I: Into<Self::Expr>>(&mut self, x: I);
 
+    #[track_caller]
     fn assert_one<I: Into<Self::Expr>>(&mut self, x: I) {
         self.assert_zero(x.into() - self.one());
     }
 
+    #[track_caller]
     fn assert_eq<I1: Into<Self::Expr>, I2: Into<Self::Expr>>(&mut self, x: I1, y: I2) {
         self.assert_zero(x.into() - y.into());
     }
 
     /// Assert that `x` is a boolean, i.e. either 0 or 1.
+    #[track_caller]
     fn assert_bool<I: Into<Self::Expr>>(&mut self, x: I) {
         let x = x.into();
         self.assert_zero(x.clone() * (x - self.one()));
     }
 
     /// Assert that `x` is ternary, i.e. either 0, 1 or 2.
+    #[track_caller]
     fn assert_tern<I: Into<Self::Expr>>(&mut self, x: I) {
         let x = x.into();
         let one = self.one();
@@ -224,6 +318,58 @@ pub trait PairBuilder: AirBuilder {
     fn preprocessed(&self) -> Self::M;
 }
 
+/// An `AirBuilder` which exposes the current row of the AIR's periodic columns.
+pub trait PeriodicAirBuilder: AirBuilder {
+    fn periodic_values(&self) -> &[Self::Var];
+}
+
+/// An `AirBuilder` which can also enforce constraints over an extension field `EF` of `F`.
+pub trait ExtensionBuilder: AirBuilder {
+    type EF: ExtensionOf<Self::F>;
+
+    type ExprEF: Clone
+        + Send
+        + Sync
+        + From<Self::EF>
+        + Add<Self::ExprEF, Output = Self::ExprEF>
+        + Sub<Self::ExprEF, Output = Self::ExprEF>
+        + Mul<Self::ExprEF, Output = Self::ExprEF>;
+
+    type VarEF: Into<Self::ExprEF> + Clone + Copy + Send + Sync;
+
+    /// Embeds a base field expression into the extension.
+    fn lift<I: Into<Self::Expr>>(&self, x: I) -> Self::ExprEF;
+
+    #[track_caller]
+    fn assert_zero_ext<I: Into<Self::ExprEF>>(&mut self, x: I);
+
+    #[track_caller]
+    fn assert_eq_ext<I1: Into<Self::ExprEF>, I2: Into<Self::ExprEF>>(&mut self, x: I1, y: I2) {
+        self.assert_zero_ext(x.into() - y.into());
+    }
+
+    #[track_caller]
+    fn assert_one_ext<I: Into<Self::ExprEF>>(&mut self, x: I) {
+        self.assert_eq_ext(x, Self::ExprEF::from(Self::EF::one()));
+    }
+}
+
+/// An `AirBuilder` with access to an auxiliary (permutation) trace over `EF`, together with the
+/// verifier challenges that trace was generated from.
+pub trait PermutationAirBuilder: ExtensionBuilder {
+    type MP: Matrix<Self::VarEF>;
+
+    type RandomVar: Into<Self::ExprEF> + Copy;
+
+    fn permutation(&self) -> Self::MP;
+
+    fn permutation_randomness(&self) -> &[Self::RandomVar];
+}
+
+/// A sub-builder whose constraints are multiplied by `condition` before being passed on.
+///
+/// Since it is itself an `AirBuilder`, filters can be nested (`builder.when(a).when(b)`), in which
+/// case the conditions multiply, and every gadget written against `AirBuilder` can run under it.
 #[derive(Debug)]
 pub struct FilteredAirBuilder<'a, AB: AirBuilder> {
     pub inner: &'a mut AB,
@@ -234,45 +380,120 @@ impl<AB: AirBuilder> FilteredAirBuilder<'_, AB> {
     pub fn condition(&self) -> AB::Expr {
         self.condition.clone()
     }
+}
+
+impl<AB: AirBuilder> AirBuilder for FilteredAirBuilder<'_, AB> {
+    type F = AB::F;
+    type Expr = AB::Expr;
+    type Var = AB::Var;
+    type M = AB::M;
 
-    pub fn main(&self) -> AB::M {
+    fn main(&self) -> Self::M {
         self.inner.main()
     }
 
-    pub fn is_first_row(&self) -> AB::Expr {
+    fn is_first_row(&self) -> Self::Expr {
         self.inner.is_first_row()
     }
 
-    pub fn is_last_row(&self) -> AB::Expr {
+    fn is_last_row(&self) -> Self::Expr {
         self.inner.is_last_row()
     }
 
-    pub fn is_transition_window(&self, size: usize) -> AB::Expr {
+    fn is_transition(&self) -> Self::Expr {
+        self.inner.is_transition()
+    }
+
+    fn is_transition_window(&self, size: usize) -> Self::Expr {
         self.inner.is_transition_window(size)
     }
 
-    pub fn zero(&self) -> AB::Expr {
+    fn is_row(&self, row: usize) -> Self::Expr {
+        self.inner.is_row(row)
+    }
+
+    fn is_row_mod(&self, residue: usize, period: usize) -> Self::Expr {
+        self.inner.is_row_mod(residue, period)
+    }
+
+    fn is_segment_start(&self, len: usize) -> Self::Expr {
+        self.inner.is_segment_start(len)
+    }
+
+    fn zero(&self) -> Self::Expr {
         self.inner.zero()
     }
-    pub fn one(&self) -> AB::Expr {
+    fn one(&self) -> Self::Expr {
         self.inner.one()
     }
-    pub fn two(&self) -> AB::Expr {
+    fn two(&self) -> Self::Expr {
         self.inner.two()
     }
-    pub fn from_u32(&self, val: u32) -> AB::Expr {
+    fn from_u32(&self, val: u32) -> Self::Expr {
         self.inner.from_u32(val)
     }
 
-    pub fn assert_zero<I: Into<AB::Expr>>(&mut self, x: I) {
+    fn require_field(&mut self, requirement: FieldRequirement) {
+        self.inner.require_field(requirement);
+    }
+
+    fn push_scope(&mut self, name: &str) {
+        self.inner.push_scope(name);
+    }
+
+    fn pop_scope(&mut self) {
+        self.inner.pop_scope();
+    }
+
+    fn assert_zero<I: Into<Self::Expr>>(&mut self, x: I) {
         self.inner.assert_zero(self.condition() * x.into());
     }
+}
 
-    pub fn assert_eq<I1: Into<AB::Expr>, I2: Into<AB::Expr>>(&mut self, x: I1, y: I2) {
-        self.assert_zero(x.into() - y.into());
+impl<AB: AirBuilderWithPublicValues> AirBuilderWithPublicValues for FilteredAirBuilder<'_, AB> {
+    type PublicVar = AB::PublicVar;
+
+    fn public_values(&self) -> &[Self::PublicVar] {
+        self.inner.public_values()
     }
+}
 
-    pub fn assert_one<I: Into<AB::Expr>>(&mut self, x: I) {
-        self.assert_zero(x.into() - self.one());
+impl<AB: PairBuilder> PairBuilder for FilteredAirBuilder<'_, AB> {
+    fn preprocessed(&self) -> Self::M {
+        self.inner.preprocessed()
+    }
+}
+
+impl<AB: PeriodicAirBuilder> PeriodicAirBuilder for FilteredAirBuilder<'_, AB> {
+    fn periodic_values(&self) -> &[Self::Var] {
+        self.inner.periodic_values()
+    }
+}
+
+impl<AB: ExtensionBuilder> ExtensionBuilder for FilteredAirBuilder<'_, AB> {
+    type EF = AB::EF;
+    type ExprEF = AB::ExprEF;
+    type VarEF = AB::VarEF;
+
+    fn lift<I: Into<Self::Expr>>(&self, x: I) -> Self::ExprEF {
+        self.inner.lift(x)
+    }
+
+    fn assert_zero_ext<I: Into<Self::ExprEF>>(&mut self, x: I) {
+        let condition = self.inner.lift(self.condition());
+        self.inner.assert_zero_ext(condition * x.into());
+    }
+}
+
+impl<AB: PermutationAirBuilder> PermutationAirBuilder for FilteredAirBuilder<'_, AB> {
+    type MP = AB::MP;
+    type RandomVar = AB::RandomVar;
+
+    fn permutation(&self) -> Self::MP {
+        self.inner.permutation()
+    }
+
+    fn permutation_randomness(&self) -> &[Self::RandomVar] {
+        self.inner.permutation_randomness()
     }
 }
@@ -2,10 +2,25 @@
 // Original authors: Plonky3 authors, 2022
 // Modifications by Ingonyama, 2025
 
-use crate::air::{Air, AirBuilder, AirBuilderWithPublicValues};
+use crate::air::{
+    Air, AirBuilder, AirBuilderWithPublicValues, BaseAirWithPublicValues, ExtensionBuilder,
+    PairBuilder, PeriodicAirBuilder, PermutationAirBuilder,
+};
+use crate::config::AirConfig;
+use crate::extension::ExtensionOf;
+use crate::field_requirement::{
+    check_field_requirements, Characteristic, FieldRequirement, FieldRequirementError,
+};
+use crate::interaction::{Interaction, InteractionAir, InteractionKind};
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+use alloc::vec;
 use alloc::vec::Vec;
-use p3_matrix::dense::{RowMajorMatrix, RowMajorMatrixView};
-use p3_matrix::stack::VerticalPair;
+use core::any::type_name;
+use core::cell::OnceCell;
+use core::fmt::{self, Debug, Display, Formatter};
+use core::panic::Location;
+use p3_matrix::dense::RowMajorMatrix;
 use p3_matrix::Matrix;
 use tracing::instrument;
 
@@ -21,59 +36,565 @@ pub fn from_bool<F: Field + Arithmetic>(b: bool) -> F {
     }
 }
 
+/// Checks the constraints of an AIR without an auxiliary trace on every row of `main`, returning
+/// the first one that fails.
+///
+/// Also fails if `main` or `public_values` do not match the shape of `air`, or if the field does
+/// not satisfy the AIR's field requirements.
 #[instrument(name = "check constraints", skip_all)]
-pub(crate) fn check_constraints<F, A>(air: &A, main: &RowMajorMatrix<F>, public_values: &Vec<F>)
+pub fn check_constraints<F, A>(
+    air: &A,
+    main: &RowMajorMatrix<F>,
+    public_values: &[F],
+) -> Result<(), ConstraintError<F, F>>
 where
     F: Field + Arithmetic,
-    A: for<'a> Air<DebugConstraintBuilder<'a, F>>,
+    A: BaseAirWithPublicValues<F> + for<'a> Air<DebugConstraintBuilder<'a, F, F>>,
 {
-    let height = main.height();
+    check_all_constraints(air, main, None, &[], public_values)
+}
 
-    (0..height).for_each(|i| {
-        let i_next = (i + 1) % height;
+/// Like `check_constraints`, for AIRs which assert extension constraints without an auxiliary
+/// trace. `EF` is the extension field the builder offers the AIR.
+#[instrument(name = "check constraints with extension", skip_all)]
+pub fn check_constraints_with_extension<F, EF, A>(
+    air: &A,
+    main: &RowMajorMatrix<F>,
+    public_values: &[F],
+) -> Result<(), ConstraintError<F, EF>>
+where
+    F: Field + Arithmetic,
+    EF: ExtensionOf<F>,
+    A: BaseAirWithPublicValues<F> + for<'a> Air<DebugConstraintBuilder<'a, F, EF>>,
+{
+    check_all_constraints(air, main, None, &[], public_values)
+}
 
-        let local_binding = main.row_slice(i);
-        let local = local_binding.as_ref().expect("row_slice returned None");
-        let next_binding = main.row_slice(i_next);
-        let next = next_binding.as_ref().expect("row_slice returned None");
-        let main = VerticalPair::new(
-            RowMajorMatrixView::new_row(&**local),
-            RowMajorMatrixView::new_row(&**next),
-        );
+/// Checks the constraints of an AIR with an auxiliary trace, which was generated from `main` and
+/// the verifier `challenges`.
+///
+/// `try_check_multi_phase_constraints` calls this for a `MultiPhaseAir`; other AIRs can generate
+/// their auxiliary trace themselves and check it here.
+#[instrument(name = "check constraints with permutation", skip_all)]
+pub fn check_constraints_with_permutation<F, EF, A>(
+    air: &A,
+    main: &RowMajorMatrix<F>,
+    permutation: &RowMajorMatrix<EF>,
+    challenges: &[EF],
+    public_values: &[F],
+) -> Result<(), ConstraintError<F, EF>>
+where
+    F: Field + Arithmetic,
+    EF: ExtensionOf<F>,
+    A: BaseAirWithPublicValues<F> + for<'a> Air<DebugConstraintBuilder<'a, F, EF>>,
+{
+    check_shape(
+        "permutation trace height does not match the main trace",
+        main.height(),
+        permutation.height(),
+    )?;
+    check_all_constraints(air, main, Some(permutation), challenges, public_values)
+}
+
+/// Fails with `ConstraintError::Shape` unless `found == expected`.
+fn check_shape<F, EF>(
+    mismatch: &'static str,
+    expected: usize,
+    found: usize,
+) -> Result<(), ConstraintError<F, EF>> {
+    if found == expected {
+        Ok(())
+    } else {
+        Err(ConstraintError::Shape {
+            mismatch,
+            expected,
+            found,
+        })
+    }
+}
+
+fn check_all_constraints<F, EF, A>(
+    air: &A,
+    main: &RowMajorMatrix<F>,
+    permutation: Option<&RowMajorMatrix<EF>>,
+    challenges: &[EF],
+    public_values: &[F],
+) -> Result<(), ConstraintError<F, EF>>
+where
+    F: Field + Arithmetic,
+    EF: ExtensionOf<F>,
+    A: BaseAirWithPublicValues<F> + for<'a> Air<DebugConstraintBuilder<'a, F, EF>>,
+{
+    let config = AirConfig::from_air(air);
+    check_shape(
+        "main trace has the wrong width",
+        config.main_width,
+        main.width(),
+    )?;
+    check_shape(
+        "permutation trace has the wrong width",
+        config.permutation_width,
+        permutation.map_or(0, |permutation| permutation.width()),
+    )?;
+    check_shape(
+        "wrong number of challenges",
+        config.num_challenges,
+        challenges.len(),
+    )?;
+    check_shape(
+        "wrong number of public values",
+        config.num_public_values,
+        public_values.len(),
+    )?;
+
+    check_field_requirements::<F>(&air.field_requirements()).map_err(|error| {
+        ConstraintError::FieldRequirement {
+            error,
+            scope: String::new(),
+            location: None,
+        }
+    })?;
+    let characteristic = Characteristic::of::<F>();
+
+    let height = main.height();
+    let window_size = config.window_size;
+    let preprocessed = air.preprocessed_trace();
+    if let Some(preprocessed) = preprocessed.as_ref() {
+        check_shape(
+            "preprocessed trace has the wrong width",
+            config.preprocessed_width,
+            preprocessed.width(),
+        )?;
+        check_shape(
+            "preprocessed trace height does not match the main trace",
+            height,
+            preprocessed.height(),
+        )?;
+    }
+    let periodic_columns = air.periodic_columns();
+    if let Some(column) = periodic_columns
+        .iter()
+        .find(|column| !column.len().is_power_of_two() || height % column.len() != 0)
+    {
+        return Err(ConstraintError::PeriodicColumn {
+            length: column.len(),
+            height,
+        });
+    }
+
+    for i in 0..height {
+        let main = window(main, i, window_size);
+        let preprocessed = match preprocessed.as_ref() {
+            Some(preprocessed) => window(preprocessed, i, window_size),
+            None => RowMajorMatrix::new(vec![], 0),
+        };
+        let permutation = match permutation {
+            Some(permutation) => window(permutation, i, window_size),
+            None => RowMajorMatrix::new(vec![], 0),
+        };
 
         let mut builder = DebugConstraintBuilder {
+            air_name: type_name::<A>(),
             row_index: i,
+            height,
             main,
+            preprocessed,
+            permutation,
+            permutation_challenges: challenges,
             public_values,
+            characteristic: &characteristic,
+            scopes: vec![],
+            periodic_values: periodic_columns
+                .iter()
+                .map(|column| column[i % column.len()])
+                .collect(),
             is_first_row: from_bool::<F>(i == 0),
             is_last_row: from_bool::<F>(i == height - 1),
-            is_transition: from_bool::<F>(i != height - 1),
+            constraint_count: 0,
+            extension_constraint_count: 0,
+            failure: OnceCell::new(),
         };
 
         air.eval(&mut builder);
-    });
+        if let Some(failure) = builder.failure.into_inner() {
+            return Err(failure);
+        }
+    }
+    Ok(())
+}
+
+/// How a constraint failed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ConstraintFailure<F, EF> {
+    /// An `assert_zero` evaluated to this nonzero value.
+    NonZero(F),
+    /// The two sides of an `assert_eq` had these different values.
+    NotEqual(F, F),
+    /// An `assert_zero_ext` evaluated to this nonzero value.
+    NonZeroExtension(EF),
 }
 
-/// An `AirBuilder` which asserts that each constraint is zero, allowing any failed constraints to
-/// be detected early.
+/// Why `check_constraints` rejected a trace.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ConstraintError<F, EF> {
+    /// A trace, the challenges or the public values do not have the shape the AIR reports.
+    Shape {
+        mismatch: &'static str,
+        expected: usize,
+        found: usize,
+    },
+    /// A periodic column's length is not a power of two dividing the trace height.
+    PeriodicColumn { length: usize, height: usize },
+    /// The AIR asked for a row selector which is not valid for the trace, such as a row past its
+    /// end.
+    Selector {
+        row: usize,
+        /// The selector call, e.g. `is_row(13)`.
+        selector: String,
+        reason: &'static str,
+        /// The scopes the selector was requested in, joined by `/`.
+        scope: String,
+    },
+    /// The field violates a requirement declared by the AIR or by a gadget it uses.
+    FieldRequirement {
+        error: FieldRequirementError,
+        /// The scopes a gadget declared the requirement in, joined by `/`.
+        scope: String,
+        /// Where a gadget declared the requirement, or `None` for the AIR's own requirements.
+        location: Option<&'static Location<'static>>,
+    },
+    /// A constraint did not hold.
+    Constraint(ConstraintViolation<F, EF>),
+}
+
+impl<F: Display, EF: Display> Display for ConstraintError<F, EF> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Shape {
+                mismatch,
+                expected,
+                found,
+            } => write!(f, "{}: expected {}, found {}", mismatch, expected, found),
+            Self::PeriodicColumn { length, height } => write!(
+                f,
+                "periodic column of length {} does not divide the trace height {}",
+                length, height
+            ),
+            Self::Selector {
+                row,
+                selector,
+                reason,
+                scope,
+            } => {
+                write!(f, "invalid selector {} on row {}", selector, row)?;
+                if !scope.is_empty() {
+                    write!(f, " in `{}`", scope)?;
+                }
+                write!(f, ": {}", reason)
+            }
+            Self::FieldRequirement {
+                error,
+                scope,
+                location,
+            } => {
+                write!(f, "{}", error)?;
+                if !scope.is_empty() {
+                    write!(f, " in `{}`", scope)?;
+                }
+                match location {
+                    Some(location) => write!(f, " at {}", location),
+                    None => Ok(()),
+                }
+            }
+            Self::Constraint(violation) => write!(f, "{}", violation),
+        }
+    }
+}
+
+impl<F: Debug + Display, EF: Debug + Display> core::error::Error for ConstraintError<F, EF> {}
+
+/// The first failing constraint found by `check_constraints`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConstraintViolation<F, EF> {
+    /// The type name of the AIR.
+    pub air_name: &'static str,
+    pub row: usize,
+    /// The position of the constraint in `get_symbolic_constraints`, or in
+    /// `get_symbolic_extension_constraints` for extension constraints.
+    pub constraint_index: usize,
+    pub failure: ConstraintFailure<F, EF>,
+    /// The scopes the constraint was asserted in, joined by `/`.
+    pub scope: String,
+    pub location: &'static Location<'static>,
+}
+
+impl<F: Display, EF: Display> Display for ConstraintViolation<F, EF> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match &self.failure {
+            ConstraintFailure::NonZero(_) => {
+                write!(f, "constraints had nonzero value on row {}", self.row)?
+            }
+            ConstraintFailure::NotEqual(x, y) => {
+                write!(f, "values didn't match on row {}: {} != {}", self.row, x, y)?
+            }
+            ConstraintFailure::NonZeroExtension(_) => write!(
+                f,
+                "extension constraints had nonzero value on row {}",
+                self.row
+            )?,
+        }
+        if !self.scope.is_empty() {
+            write!(f, " in `{}`", self.scope)?;
+        }
+        write!(f, " at {}", self.location)?;
+        match &self.failure {
+            ConstraintFailure::NonZero(value) => write!(
+                f,
+                " (constraint {} of {} evaluated to {})",
+                self.constraint_index, self.air_name, value
+            ),
+            ConstraintFailure::NotEqual(..) => write!(
+                f,
+                " (constraint {} of {})",
+                self.constraint_index, self.air_name
+            ),
+            ConstraintFailure::NonZeroExtension(value) => write!(
+                f,
+                " (extension constraint {} of {} evaluated to {})",
+                self.constraint_index, self.air_name, value
+            ),
+        }
+    }
+}
+
+/// Copies the `window_size` rows starting at row `i` (wrapping around) into a new matrix.
+fn window<T: Clone + Send + Sync>(
+    trace: &RowMajorMatrix<T>,
+    i: usize,
+    window_size: usize,
+) -> RowMajorMatrix<T> {
+    let height = trace.height();
+    let values = (0..window_size)
+        .flat_map(|offset| {
+            let row = trace
+                .row_slice((i + offset) % height)
+                .expect("row_slice returned None");
+            row.to_vec()
+        })
+        .collect();
+    RowMajorMatrix::new(values, trace.width())
+}
+
+/// One AIR's concrete trace, as taken into account by `check_bus_balance`.
+pub struct BusInstance<'a, F: Field + Arithmetic> {
+    air_name: &'static str,
+    interactions: Vec<Interaction<F>>,
+    preprocessed: Option<RowMajorMatrix<F>>,
+    main: &'a RowMajorMatrix<F>,
+}
+
+impl<'a, F: Field + Arithmetic> BusInstance<'a, F> {
+    pub fn new<A>(air: &A, main: &'a RowMajorMatrix<F>, public_values: &[F]) -> Self
+    where
+        A: InteractionAir<F> + BaseAirWithPublicValues<F>,
+    {
+        assert_eq!(main.width(), air.width(), "main trace has the wrong width");
+        assert_eq!(
+            public_values.len(),
+            air.num_public_values(),
+            "wrong number of public values"
+        );
+        Self {
+            air_name: type_name::<A>(),
+            interactions: air.interactions(),
+            preprocessed: air.preprocessed_trace(),
+            main,
+        }
+    }
+}
+
+/// A single send or receive of a tuple, as found in a trace.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BusContribution<F> {
+    pub air_name: &'static str,
+    pub row: usize,
+    pub kind: InteractionKind,
+    pub multiplicity: F,
+}
+
+/// A tuple which was sent a different number of times than it was received.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BusImbalance<F> {
+    pub bus: usize,
+    pub tuple: Vec<F>,
+    /// Total sends minus total receives.
+    pub net_multiplicity: F,
+    pub contributions: Vec<BusContribution<F>>,
+}
+
+/// Returned by `check_bus_balance` when some bus is unbalanced.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BusBalanceError<F> {
+    pub imbalances: Vec<BusImbalance<F>>,
+}
+
+impl<F: Display> Display for BusBalanceError<F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} unbalanced tuple(s)", self.imbalances.len())?;
+        for imbalance in &self.imbalances {
+            write!(f, "\n  bus {}: (", imbalance.bus)?;
+            for (i, value) in imbalance.tuple.iter().enumerate() {
+                if i > 0 {
+                    write!(f, ", ")?;
+                }
+                write!(f, "{}", value)?;
+            }
+            write!(f, ") has net multiplicity {}", imbalance.net_multiplicity)?;
+            for c in &imbalance.contributions {
+                write!(
+                    f,
+                    "\n    {:?} x{} by {} on row {}",
+                    c.kind, c.multiplicity, c.air_name, c.row
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<F: Debug + Display> core::error::Error for BusBalanceError<F> {}
+
+/// Collects every send and receive on every bus from the given traces, and reports the tuples
+/// whose sends and receives do not cancel out.
+#[instrument(name = "check bus balance", skip_all)]
+pub fn check_bus_balance<F: Field + Arithmetic>(
+    instances: &[BusInstance<'_, F>],
+) -> Result<(), BusBalanceError<F>> {
+    let mut buses = BTreeMap::<(usize, Vec<u8>), BusImbalance<F>>::new();
+
+    for instance in instances {
+        for r in 0..instance.main.height() {
+            let main_row = instance
+                .main
+                .row_slice(r)
+                .expect("row_slice returned None")
+                .to_vec();
+            let preprocessed_row = match instance.preprocessed.as_ref() {
+                Some(preprocessed) => preprocessed
+                    .row_slice(r)
+                    .expect("row_slice returned None")
+                    .to_vec(),
+                None => vec![],
+            };
+
+            for interaction in &instance.interactions {
+                let multiplicity = interaction
+                    .multiplicity
+                    .apply::<F, F>(&preprocessed_row, &main_row);
+                if multiplicity == F::zero() {
+                    continue;
+                }
+                let tuple = interaction.eval_fields(&preprocessed_row, &main_row);
+                let key = (
+                    interaction.bus,
+                    tuple.iter().flat_map(|value| value.to_bytes_le()).collect(),
+                );
+                let entry = buses.entry(key).or_insert_with(|| BusImbalance {
+                    bus: interaction.bus,
+                    tuple,
+                    net_multiplicity: F::zero(),
+                    contributions: vec![],
+                });
+                entry.net_multiplicity = entry.net_multiplicity
+                    + interaction.eval_signed_multiplicity(&preprocessed_row, &main_row);
+                entry.contributions.push(BusContribution {
+                    air_name: instance.air_name,
+                    row: r,
+                    kind: interaction.kind,
+                    multiplicity,
+                });
+            }
+        }
+    }
+
+    let imbalances: Vec<_> = buses
+        .into_values()
+        .filter(|imbalance| imbalance.net_multiplicity != F::zero())
+        .collect();
+    if imbalances.is_empty() {
+        Ok(())
+    } else {
+        Err(BusBalanceError { imbalances })
+    }
+}
+
+/// An `AirBuilder` which evaluates each constraint on one row of a concrete trace, and records the
+/// first one that is not zero, the first field requirement a gadget declares that the field does
+/// not satisfy, or the first selector which is not valid for the trace.
 #[derive(Debug)]
-pub struct DebugConstraintBuilder<'a, F: Field + Arithmetic> {
+pub struct DebugConstraintBuilder<'a, F: Field + Arithmetic, EF: ExtensionOf<F> = F> {
+    air_name: &'static str,
     row_index: usize,
-    main: VerticalPair<RowMajorMatrixView<'a, F>, RowMajorMatrixView<'a, F>>,
+    height: usize,
+    main: RowMajorMatrix<F>,
+    preprocessed: RowMajorMatrix<F>,
+    permutation: RowMajorMatrix<EF>,
+    permutation_challenges: &'a [EF],
     public_values: &'a [F],
+    characteristic: &'a Characteristic,
+    periodic_values: Vec<F>,
+    scopes: Vec<String>,
     is_first_row: F,
     is_last_row: F,
-    is_transition: F,
+    /// The number of base and extension constraints asserted on this row so far.
+    constraint_count: usize,
+    extension_constraint_count: usize,
+    /// Selectors are requested through `&self`, so the failure is set through a shared reference.
+    failure: OnceCell<ConstraintError<F, EF>>,
+}
+
+impl<F: Field + Arithmetic, EF: ExtensionOf<F>> DebugConstraintBuilder<'_, F, EF> {
+    /// Records a failure, unless an earlier constraint on this row has already failed.
+    fn fail(
+        &mut self,
+        constraint_index: usize,
+        failure: ConstraintFailure<F, EF>,
+        location: &'static Location<'static>,
+    ) {
+        if self.failure.get().is_none() {
+            let _ = self.failure.set(ConstraintError::Constraint(ConstraintViolation {
+                air_name: self.air_name,
+                row: self.row_index,
+                constraint_index,
+                failure,
+                scope: self.scopes.join("/"),
+                location,
+            }));
+        }
+    }
+
+    /// Records an invalid selector, unless an earlier constraint on this row has already failed,
+    /// and returns zero in its place.
+    fn invalid_selector(&self, selector: fmt::Arguments<'_>, reason: &'static str) -> F {
+        let _ = self.failure.set(ConstraintError::Selector {
+            row: self.row_index,
+            selector: selector.to_string(),
+            reason,
+            scope: self.scopes.join("/"),
+        });
+        F::zero()
+    }
 }
 
-impl<'a, F> AirBuilder for DebugConstraintBuilder<'a, F>
+impl<F, EF> AirBuilder for DebugConstraintBuilder<'_, F, EF>
 where
     F: Field + Arithmetic,
+    EF: ExtensionOf<F>,
 {
     type F = F;
     type Expr = F;
     type Var = F;
-    type M = VerticalPair<RowMajorMatrixView<'a, F>, RowMajorMatrixView<'a, F>>;
+    type M = RowMajorMatrix<F>;
 
     fn main(&self) -> Self::M {
         self.main.clone()
@@ -88,11 +609,34 @@ where
     }
 
     fn is_transition_window(&self, size: usize) -> Self::Expr {
-        if size == 2 {
-            self.is_transition.clone()
-        } else {
-            panic!("only supports a window size of 2")
+        if !(2..=self.main.height()).contains(&size) {
+            return self.invalid_selector(
+                format_args!("is_transition_window({})", size),
+                "the window must have at least two rows and fit in the AIR's window size",
+            );
+        }
+        from_bool(self.row_index + size - 1 < self.height)
+    }
+
+    fn is_row(&self, row: usize) -> Self::Expr {
+        if row >= self.height {
+            return self.invalid_selector(
+                format_args!("is_row({})", row),
+                "the row is past the end of the trace, so the selector can never fire",
+            );
+        }
+        from_bool(self.row_index == row)
+    }
+
+    fn is_row_mod(&self, residue: usize, period: usize) -> Self::Expr {
+        if !(period.is_power_of_two() && residue < period && self.height % period == 0) {
+            return self.invalid_selector(
+                format_args!("is_row_mod({}, {})", residue, period),
+                "the period must be a power of two dividing the trace height, and the residue \
+                 smaller than the period",
+            );
         }
+        from_bool(self.row_index % period == residue)
     }
 
     fn zero(&self) -> Self::Expr {
@@ -105,30 +649,112 @@ where
         F::from_u32(val)
     }
 
+    fn require_field(&mut self, requirement: FieldRequirement) {
+        if !requirement.is_satisfied_by(self.characteristic) {
+            let _ = self.failure.set(ConstraintError::FieldRequirement {
+                error: FieldRequirementError {
+                    requirement,
+                    characteristic: self.characteristic.clone(),
+                },
+                scope: self.scopes.join("/"),
+                location: Some(Location::caller()),
+            });
+        }
+    }
+
+    fn push_scope(&mut self, name: &str) {
+        self.scopes.push(name.to_string());
+    }
+
+    fn pop_scope(&mut self) {
+        self.scopes.pop();
+    }
+
     fn assert_zero<I: Into<Self::Expr>>(&mut self, x: I) {
-        assert_eq!(
-            x.into(),
-            F::zero(),
-            "constraints had nonzero value on row {}",
-            self.row_index
-        );
+        let x = x.into();
+        if x != F::zero() {
+            self.fail(
+                self.constraint_count,
+                ConstraintFailure::NonZero(x),
+                Location::caller(),
+            );
+        }
+        self.constraint_count += 1;
     }
 
     fn assert_eq<I1: Into<Self::Expr>, I2: Into<Self::Expr>>(&mut self, x: I1, y: I2) {
         let x = x.into();
         let y = y.into();
-        assert_eq!(
-            x, y,
-            "values didn't match on row {}: {} != {}",
-            self.row_index, x, y
-        );
+        if x != y {
+            self.fail(
+                self.constraint_count,
+                ConstraintFailure::NotEqual(x, y),
+                Location::caller(),
+            );
+        }
+        self.constraint_count += 1;
     }
 }
 
-impl<F: Field + Arithmetic> AirBuilderWithPublicValues for DebugConstraintBuilder<'_, F> {
+impl<F: Field + Arithmetic, EF: ExtensionOf<F>> AirBuilderWithPublicValues
+    for DebugConstraintBuilder<'_, F, EF>
+{
     type PublicVar = Self::F;
 
     fn public_values(&self) -> &[Self::F] {
         self.public_values
     }
 }
+
+impl<F: Field + Arithmetic, EF: ExtensionOf<F>> PairBuilder for DebugConstraintBuilder<'_, F, EF> {
+    fn preprocessed(&self) -> Self::M {
+        self.preprocessed.clone()
+    }
+}
+
+impl<F: Field + Arithmetic, EF: ExtensionOf<F>> PeriodicAirBuilder
+    for DebugConstraintBuilder<'_, F, EF>
+{
+    fn periodic_values(&self) -> &[Self::Var] {
+        &self.periodic_values
+    }
+}
+
+impl<F: Field + Arithmetic, EF: ExtensionOf<F>> ExtensionBuilder
+    for DebugConstraintBuilder<'_, F, EF>
+{
+    type EF = EF;
+    type ExprEF = EF;
+    type VarEF = EF;
+
+    fn lift<I: Into<Self::Expr>>(&self, x: I) -> Self::ExprEF {
+        EF::from_base(x.into())
+    }
+
+    fn assert_zero_ext<I: Into<Self::ExprEF>>(&mut self, x: I) {
+        let x = x.into();
+        if x != EF::zero() {
+            self.fail(
+                self.extension_constraint_count,
+                ConstraintFailure::NonZeroExtension(x),
+                Location::caller(),
+            );
+        }
+        self.extension_constraint_count += 1;
+    }
+}
+
+impl<F: Field + Arithmetic, EF: ExtensionOf<F>> PermutationAirBuilder
+    for DebugConstraintBuilder<'_, F, EF>
+{
+    type MP = RowMajorMatrix<EF>;
+    type RandomVar = EF;
+
+    fn permutation(&self) -> Self::MP {
+        self.permutation.clone()
+    }
+
+    fn permutation_randomness(&self) -> &[Self::RandomVar] {
+        self.permutation_challenges
+    }
+}
@@ -0,0 +1,99 @@
+//! Structs naming the columns of a trace row, as derived by `#[derive(AirColumns)]`.
+//!
+//! A column struct is generic over the cell type `T`, and each of its fields is a `T`, an array of
+//! them, or another column struct. With `#[repr(C)]` such a struct has the layout of `[T; N]`, so a
+//! row of the trace can be borrowed as the struct, and instantiating it with `T = usize` gives the
+//! index of every column.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::mem::{align_of, size_of, MaybeUninit};
+use core::slice;
+
+pub use icicle_air_derive::AirColumns;
+
+/// A struct of `NUM_COLUMNS` cells of type `T`, laid out exactly like `[T; NUM_COLUMNS]`.
+///
+/// # Safety
+///
+/// Implementors must be `#[repr(C)]` and consist only of `T`s, arrays of `T`s and other
+/// `AirColumns<T>` structs, in the order of `column_names`. `#[derive(AirColumns)]` checks this.
+pub unsafe trait AirColumns<T>: Sized {
+    const NUM_COLUMNS: usize;
+
+    /// The name of every column, such as `a_prime[2][3][17]` or `rounds[1].state.row0[3][0]`.
+    fn column_names() -> Vec<String>;
+}
+
+/// Checks, at compile time where possible, that `C` really has the layout of `[T; NUM_COLUMNS]`.
+const fn assert_layout<C: AirColumns<T>, T>() {
+    assert!(
+        size_of::<C>() == C::NUM_COLUMNS * size_of::<T>() && align_of::<C>() == align_of::<T>(),
+        "column struct does not have the layout of an array of cells"
+    );
+}
+
+/// Views a row of the trace as the column struct `C`.
+pub fn borrow_columns<C: AirColumns<T>, T>(row: &[T]) -> &C {
+    assert_layout::<C, T>();
+    assert_eq!(row.len(), C::NUM_COLUMNS, "row has the wrong width");
+    // SAFETY: `C` has the layout of `[T; NUM_COLUMNS]`, which is that of `row`.
+    unsafe { &*row.as_ptr().cast::<C>() }
+}
+
+/// Views a row of the trace as the column struct `C`, mutably.
+pub fn borrow_columns_mut<C: AirColumns<T>, T>(row: &mut [T]) -> &mut C {
+    assert_layout::<C, T>();
+    assert_eq!(row.len(), C::NUM_COLUMNS, "row has the wrong width");
+    // SAFETY: as in `borrow_columns`, and `row` is borrowed mutably for the same lifetime.
+    unsafe { &mut *row.as_mut_ptr().cast::<C>() }
+}
+
+/// Views the values of a whole trace, row after row, as a slice of column structs `C`.
+pub fn borrow_rows<C: AirColumns<T>, T>(values: &[T]) -> &[C] {
+    assert_layout::<C, T>();
+    assert!(C::NUM_COLUMNS > 0, "column struct has no columns");
+    assert_eq!(
+        values.len() % C::NUM_COLUMNS,
+        0,
+        "values do not split into whole rows"
+    );
+    // SAFETY: `C` has the layout of `[T; NUM_COLUMNS]`, and `values` holds a whole number of them.
+    unsafe { slice::from_raw_parts(values.as_ptr().cast::<C>(), values.len() / C::NUM_COLUMNS) }
+}
+
+/// Views the values of a whole trace, row after row, as a mutable slice of column structs `C`.
+pub fn borrow_rows_mut<C: AirColumns<T>, T>(values: &mut [T]) -> &mut [C] {
+    assert_layout::<C, T>();
+    assert!(C::NUM_COLUMNS > 0, "column struct has no columns");
+    assert_eq!(
+        values.len() % C::NUM_COLUMNS,
+        0,
+        "values do not split into whole rows"
+    );
+    let height = values.len() / C::NUM_COLUMNS;
+    // SAFETY: as in `borrow_rows`, and `values` is borrowed mutably for the same lifetime.
+    unsafe { slice::from_raw_parts_mut(values.as_mut_ptr().cast::<C>(), height) }
+}
+
+/// The column struct holding the index of each column, for looking columns up by field.
+pub const fn column_map<C: AirColumns<usize>>() -> C {
+    assert_layout::<C, usize>();
+    let mut map = MaybeUninit::<C>::uninit();
+    let indices = map.as_mut_ptr().cast::<usize>();
+    let mut i = 0;
+    while i < C::NUM_COLUMNS {
+        // SAFETY: `C` has the layout of `[usize; NUM_COLUMNS]`.
+        unsafe { indices.add(i).write(i) };
+        i += 1;
+    }
+    // SAFETY: every cell of `C` has been written, and any `usize` is a valid cell.
+    unsafe { map.assume_init() }
+}
+
+#[doc(hidden)]
+pub mod __private {
+    pub use alloc::format;
+    pub use alloc::string::String;
+    pub use alloc::vec::Vec;
+}
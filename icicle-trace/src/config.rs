@@ -0,0 +1,43 @@
+//! The layout an AIR reports about itself.
+
+use icicle_core::field::Field;
+use icicle_core::traits::Arithmetic;
+
+use crate::air::BaseAirWithPublicValues;
+
+/// The shape of an AIR: how many columns each trace has, how many values the verifier supplies,
+/// and how many rows each constraint can see.
+///
+/// Builders and analysis functions take their setup from an `AirConfig` derived from the AIR
+/// itself, so the constraints they produce always agree with the AIR's own description.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct AirConfig {
+    pub main_width: usize,
+    pub preprocessed_width: usize,
+    /// The width of the auxiliary (permutation) trace, over the extension field.
+    pub permutation_width: usize,
+    /// The number of verifier challenges the auxiliary trace depends on.
+    pub num_challenges: usize,
+    pub num_public_values: usize,
+    pub num_periodic_columns: usize,
+    pub window_size: usize,
+}
+
+impl AirConfig {
+    /// Reads the layout that `air` reports about itself.
+    pub fn from_air<F, A>(air: &A) -> Self
+    where
+        F: Field + Arithmetic,
+        A: BaseAirWithPublicValues<F> + ?Sized,
+    {
+        Self {
+            main_width: air.width(),
+            preprocessed_width: air.preprocessed_width(),
+            permutation_width: air.permutation_width(),
+            num_challenges: air.num_challenges(),
+            num_public_values: air.num_public_values(),
+            num_periodic_columns: air.periodic_columns().len(),
+            window_size: air.window_size(),
+        }
+    }
+}
@@ -0,0 +1,209 @@
+//! An AIR whose columns and constraints are data rather than code.
+//!
+//! `DynamicAir` holds named main columns, a number of public values and a list of constraints as
+//! `SymbolicExpression`s, and evaluates them on any builder by rebuilding each expression tree from
+//! the builder's variables. This lets tools and tests assemble AIRs at runtime.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use icicle_core::field::Field;
+use icicle_core::traits::Arithmetic;
+use p3_matrix::Matrix;
+
+use crate::air::{Air, AirBuilderWithPublicValues, BaseAir, BaseAirWithPublicValues};
+use crate::interaction::row;
+use crate::symbolic_expression::SymbolicExpression;
+use crate::symbolic_variable::{Entry, SymbolicVariable};
+
+/// An AIR over named main columns and public values, with runtime-defined constraints.
+///
+/// Constraints may refer to main columns at any row offset, public values, and the row selectors;
+/// the window grows to fit the largest offset used.
+#[derive(Clone, Debug)]
+pub struct DynamicAir<F: Field + Arithmetic> {
+    column_names: Vec<String>,
+    num_public_values: usize,
+    window_size: usize,
+    constraints: Vec<SymbolicExpression<F>>,
+}
+
+impl<F: Field + Arithmetic> DynamicAir<F> {
+    /// An AIR with the given main columns and public values, and no constraints yet.
+    pub fn new(column_names: Vec<String>, num_public_values: usize) -> Self {
+        for (i, name) in column_names.iter().enumerate() {
+            assert!(
+                !column_names[..i].contains(name),
+                "column `{}` is declared twice",
+                name
+            );
+        }
+        Self {
+            column_names,
+            num_public_values,
+            window_size: 2,
+            constraints: Vec::new(),
+        }
+    }
+
+    pub fn column_names(&self) -> &[String] {
+        &self.column_names
+    }
+
+    /// The index of the main column called `name`.
+    pub fn column(&self, name: &str) -> Option<usize> {
+        self.column_names.iter().position(|column| column == name)
+    }
+
+    pub fn constraints(&self) -> &[SymbolicExpression<F>] {
+        &self.constraints
+    }
+
+    /// The column called `name` on the row `offset` rows below the current one.
+    pub fn main(&self, name: &str, offset: usize) -> SymbolicVariable<F> {
+        let index = self
+            .column(name)
+            .unwrap_or_else(|| panic!("unknown column `{}`", name));
+        SymbolicVariable::new(Entry::Main { offset }, index)
+    }
+
+    /// The column called `name` on the current row.
+    pub fn local(&self, name: &str) -> SymbolicVariable<F> {
+        self.main(name, 0)
+    }
+
+    /// The column called `name` on the next row.
+    pub fn next(&self, name: &str) -> SymbolicVariable<F> {
+        self.main(name, 1)
+    }
+
+    /// The public value at `index`.
+    pub fn public(&self, index: usize) -> SymbolicVariable<F> {
+        assert!(
+            index < self.num_public_values,
+            "public value {} is out of range",
+            index
+        );
+        SymbolicVariable::new(Entry::Public, index)
+    }
+
+    /// Adds the constraint that `constraint` is zero on every row.
+    ///
+    /// Panics if it refers to anything other than this AIR's main columns and public values.
+    pub fn assert_zero<I: Into<SymbolicExpression<F>>>(&mut self, constraint: I) {
+        let constraint = constraint.into();
+        let mut max_offset = 0;
+        for_each_variable(&constraint, &mut |variable| match variable.entry {
+            Entry::Main { offset } => {
+                assert!(
+                    variable.index < self.column_names.len(),
+                    "main column {} is out of range",
+                    variable.index
+                );
+                max_offset = max_offset.max(offset);
+            }
+            Entry::Public => assert!(
+                variable.index < self.num_public_values,
+                "public value {} is out of range",
+                variable.index
+            ),
+            entry => panic!(
+                "DynamicAir constraints may only refer to main columns and public values, not {:?}",
+                entry
+            ),
+        });
+        self.window_size = self.window_size.max(max_offset + 1);
+        self.constraints.push(constraint);
+    }
+
+    /// Adds the constraint that `x` and `y` are equal on every row.
+    pub fn assert_eq<I1, I2>(&mut self, x: I1, y: I2)
+    where
+        I1: Into<SymbolicExpression<F>>,
+        I2: Into<SymbolicExpression<F>>,
+    {
+        self.assert_zero(x.into() - y.into());
+    }
+}
+
+impl<F: Field + Arithmetic> BaseAir<F> for DynamicAir<F> {
+    fn width(&self) -> usize {
+        self.column_names.len()
+    }
+
+    fn window_size(&self) -> usize {
+        self.window_size
+    }
+}
+
+impl<F: Field + Arithmetic> BaseAirWithPublicValues<F> for DynamicAir<F> {
+    fn num_public_values(&self) -> usize {
+        self.num_public_values
+    }
+}
+
+impl<AB> Air<AB> for DynamicAir<AB::F>
+where
+    AB: AirBuilderWithPublicValues,
+    AB::F: Arithmetic,
+{
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let rows: Vec<_> = (0..main.height()).map(|r| row(&main, r)).collect();
+        for constraint in &self.constraints {
+            let constraint = eval_expression(builder, &rows, constraint);
+            builder.assert_zero(constraint);
+        }
+    }
+}
+
+/// Rebuilds `expression` from the variables and selectors of `builder`.
+fn eval_expression<AB>(
+    builder: &AB,
+    rows: &[Vec<AB::Var>],
+    expression: &SymbolicExpression<AB::F>,
+) -> AB::Expr
+where
+    AB: AirBuilderWithPublicValues,
+    AB::F: Arithmetic,
+{
+    let eval = |x: &SymbolicExpression<AB::F>| eval_expression(builder, rows, x);
+    match expression {
+        SymbolicExpression::Variable(variable) => match variable.entry {
+            Entry::Main { offset } => rows[offset][variable.index].into(),
+            Entry::Public => builder.public_values()[variable.index].clone().into(),
+            entry => unreachable!("constraints were checked not to refer to {:?}", entry),
+        },
+        SymbolicExpression::IsFirstRow => builder.is_first_row(),
+        SymbolicExpression::IsLastRow => builder.is_last_row(),
+        SymbolicExpression::IsTransition => builder.is_transition(),
+        SymbolicExpression::IsTransitionWindow(size) => builder.is_transition_window(*size),
+        SymbolicExpression::IsRow(row) => builder.is_row(*row),
+        SymbolicExpression::IsPeriodicRow { residue, period } => {
+            builder.is_row_mod(*residue, *period)
+        }
+        SymbolicExpression::Constant(c) => (*c).into(),
+        SymbolicExpression::Add { x, y, .. } => eval(x) + eval(y),
+        SymbolicExpression::Sub { x, y, .. } => eval(x) - eval(y),
+        SymbolicExpression::Neg { x, .. } => builder.zero() - eval(x),
+        SymbolicExpression::Mul { x, y, .. } => eval(x) * eval(y),
+    }
+}
+
+/// Calls `f` on every variable in `expression`.
+fn for_each_variable<F: Field + Arithmetic>(
+    expression: &SymbolicExpression<F>,
+    f: &mut impl FnMut(&SymbolicVariable<F>),
+) {
+    match expression {
+        SymbolicExpression::Variable(variable) => f(variable),
+        SymbolicExpression::Add { x, y, .. }
+        | SymbolicExpression::Sub { x, y, .. }
+        | SymbolicExpression::Mul { x, y, .. } => {
+            for_each_variable(x, f);
+            for_each_variable(y, f);
+        }
+        SymbolicExpression::Neg { x, .. } => for_each_variable(x, f),
+        _ => {}
+    }
+}
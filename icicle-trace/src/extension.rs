@@ -0,0 +1,68 @@
+//! Extension fields usable in extension-field constraints.
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+use icicle_babybear::field::{ExtensionField as BabyBearExtension, ScalarField as BabyBear};
+use icicle_core::bignum::BigNum;
+use icicle_core::field::Field;
+use icicle_core::traits::Arithmetic;
+
+/// A field containing `F` as a subfield, e.g. the BabyBear quartic extension over BabyBear.
+pub trait ExtensionOf<F>: Field + Arithmetic {
+    /// The degree of `Self` over `F`.
+    const DEGREE: usize;
+
+    /// Embeds a base field element.
+    fn from_base(value: F) -> Self;
+
+    /// Builds an element from its `DEGREE` coefficients over `F`, lowest degree first.
+    fn from_base_slice(coeffs: &[F]) -> Self;
+
+    /// The `DEGREE` coefficients of `self` over `F`, lowest degree first; the inverse of
+    /// `from_base_slice`.
+    fn as_base_slice(&self) -> Vec<F>;
+}
+
+/// Every field is a degree one extension of itself.
+impl<F: Field + Arithmetic> ExtensionOf<F> for F {
+    const DEGREE: usize = 1;
+
+    fn from_base(value: F) -> Self {
+        value
+    }
+
+    fn from_base_slice(coeffs: &[F]) -> Self {
+        assert_eq!(coeffs.len(), 1, "expected a single coefficient");
+        coeffs[0]
+    }
+
+    fn as_base_slice(&self) -> Vec<F> {
+        vec![*self]
+    }
+}
+
+impl ExtensionOf<BabyBear> for BabyBearExtension {
+    const DEGREE: usize = 4;
+
+    fn from_base(value: BabyBear) -> Self {
+        Self::from_base_slice(&[value, BabyBear::zero(), BabyBear::zero(), BabyBear::zero()])
+    }
+
+    fn from_base_slice(coeffs: &[BabyBear]) -> Self {
+        let degree = <Self as ExtensionOf<BabyBear>>::DEGREE;
+        assert_eq!(coeffs.len(), degree, "expected {} coefficients", degree);
+        // The extension is stored as its coefficients over the base field, lowest degree first.
+        let bytes: Vec<u8> = coeffs.iter().flat_map(|c| c.to_bytes_le()).collect();
+        Self::from_bytes_le(&bytes)
+    }
+
+    fn as_base_slice(&self) -> Vec<BabyBear> {
+        let degree = <Self as ExtensionOf<BabyBear>>::DEGREE;
+        let bytes = self.to_bytes_le();
+        bytes
+            .chunks(bytes.len() / degree)
+            .map(BabyBear::from_bytes_le)
+            .collect()
+    }
+}
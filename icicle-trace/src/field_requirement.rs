@@ -0,0 +1,153 @@
+//! Assumptions that AIRs and gadgets make about the characteristic of the field they run over.
+
+use alloc::vec::Vec;
+use core::fmt::{self, Display, Formatter};
+
+use icicle_core::field::Field;
+use icicle_core::traits::Arithmetic;
+
+/// A bound on the characteristic `P` of the field, declared by the AIR or gadget named `source`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct FieldRequirement {
+    pub source: &'static str,
+    /// `P` must be strictly greater than this.
+    pub lower: u64,
+    /// If set, `P` must be strictly less than this.
+    pub upper: Option<u64>,
+}
+
+impl FieldRequirement {
+    /// Requires `P > lower`.
+    pub const fn greater_than(source: &'static str, lower: u64) -> Self {
+        Self {
+            source,
+            lower,
+            upper: None,
+        }
+    }
+
+    /// Requires `lower < P < upper`.
+    pub const fn between(source: &'static str, lower: u64, upper: u64) -> Self {
+        Self {
+            source,
+            lower,
+            upper: Some(upper),
+        }
+    }
+
+    pub fn is_satisfied_by(&self, characteristic: &Characteristic) -> bool {
+        let p = characteristic.to_u64();
+        let above = p.is_none_or(|p| p > self.lower);
+        let below = self
+            .upper
+            .is_none_or(|upper| p.is_some_and(|p| p < upper));
+        above && below
+    }
+
+    /// Checks this requirement against the characteristic of `F`.
+    pub fn check<F: Field + Arithmetic>(&self) -> Result<(), FieldRequirementError> {
+        check_field_requirements::<F>(core::slice::from_ref(self))
+    }
+}
+
+impl Display for FieldRequirement {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.upper {
+            Some(upper) => write!(f, "{} requires {} < P < {}", self.source, self.lower, upper),
+            None => write!(f, "{} requires P > {}", self.source, self.lower),
+        }
+    }
+}
+
+/// Checks every requirement against the characteristic of `F`, failing on the first mismatch.
+pub fn check_field_requirements<F: Field + Arithmetic>(
+    requirements: &[FieldRequirement],
+) -> Result<(), FieldRequirementError> {
+    let characteristic = Characteristic::of::<F>();
+    match requirements
+        .iter()
+        .find(|requirement| !requirement.is_satisfied_by(&characteristic))
+    {
+        Some(requirement) => Err(FieldRequirementError {
+            requirement: *requirement,
+            characteristic,
+        }),
+        None => Ok(()),
+    }
+}
+
+/// The characteristic of a prime field, as little-endian bytes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Characteristic {
+    le_bytes: Vec<u8>,
+}
+
+impl Characteristic {
+    /// Computes the characteristic of `F` as `(-1) + 1` over the integers.
+    pub fn of<F: Field + Arithmetic>() -> Self {
+        let mut le_bytes = (F::zero() - F::one()).to_bytes_le();
+        let mut carry = true;
+        for byte in le_bytes.iter_mut() {
+            if !carry {
+                break;
+            }
+            let (sum, overflow) = byte.overflowing_add(1);
+            *byte = sum;
+            carry = overflow;
+        }
+        if carry {
+            le_bytes.push(1);
+        }
+        while le_bytes.last() == Some(&0) {
+            le_bytes.pop();
+        }
+        Self { le_bytes }
+    }
+
+    /// Returns the characteristic if it fits in a `u64`.
+    pub fn to_u64(&self) -> Option<u64> {
+        if self.le_bytes.len() > 8 {
+            return None;
+        }
+        Some(
+            self.le_bytes
+                .iter()
+                .rev()
+                .fold(0, |acc, &byte| (acc << 8) | byte as u64),
+        )
+    }
+}
+
+impl Display for Characteristic {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.to_u64() {
+            Some(p) => write!(f, "{}", p),
+            None => {
+                write!(f, "0x")?;
+                self.le_bytes
+                    .iter()
+                    .rev()
+                    .try_for_each(|byte| write!(f, "{:02x}", byte))
+            }
+        }
+    }
+}
+
+/// Returned when the field in use violates a declared `FieldRequirement`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FieldRequirementError {
+    pub requirement: FieldRequirement,
+    pub characteristic: Characteristic,
+}
+
+impl Display for FieldRequirementError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}, but the field has characteristic {}",
+            self.requirement, self.characteristic
+        )
+    }
+}
+
+impl core::error::Error for FieldRequirementError {}
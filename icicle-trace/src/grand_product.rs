@@ -0,0 +1,132 @@
+//! A grand-product argument showing that two groups of columns hold the same multiset of tuples.
+//!
+//! Each side is a list of tuples per row, and each field of a tuple is a `VirtualPairCol`. With
+//! challenges `alpha` and `beta`, a tuple `v` is fingerprinted as `alpha + sum_j beta^(j + 1) v_j`.
+//! A single auxiliary column `z` accumulates, row by row, the product of the left fingerprints
+//! divided by the product of the right fingerprints, and must end at one.
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+use icicle_core::bignum::BigNum;
+use icicle_core::field::Field;
+use icicle_core::traits::Arithmetic;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use tracing::instrument;
+
+use crate::air::{ExtensionBuilder, PairBuilder, PermutationAirBuilder};
+use crate::extension::ExtensionOf;
+use crate::interaction::row;
+use crate::virtual_column::VirtualPairCol;
+
+/// The number of challenges the grand-product argument uses: `alpha` and `beta`.
+pub const GRAND_PRODUCT_NUM_CHALLENGES: usize = 2;
+
+/// Two groups of tuples which must be permutations of each other across the whole trace.
+///
+/// The first-row constraint has degree two more than the number of tuples on the larger side, so
+/// keep those lists short.
+#[derive(Clone, Debug)]
+pub struct GrandProduct<F: Field + Arithmetic> {
+    pub left: Vec<Vec<VirtualPairCol<F>>>,
+    pub right: Vec<Vec<VirtualPairCol<F>>>,
+}
+
+impl<F: Field + Arithmetic> GrandProduct<F> {
+    pub fn new(left: Vec<Vec<VirtualPairCol<F>>>, right: Vec<Vec<VirtualPairCol<F>>>) -> Self {
+        let arity = left.first().or(right.first()).map_or(0, Vec::len);
+        assert!(
+            left.iter()
+                .chain(right.iter())
+                .all(|tuple| tuple.len() == arity),
+            "all tuples of a grand product must have the same length"
+        );
+        Self { left, right }
+    }
+
+    /// Constrains the running product stored in auxiliary column `column`.
+    pub fn eval<AB>(&self, builder: &mut AB, column: usize, alpha: AB::ExprEF, beta: AB::ExprEF)
+    where
+        AB: PermutationAirBuilder<F = F> + PairBuilder,
+    {
+        let main = builder.main();
+        let preprocessed = builder.preprocessed();
+        let permutation = builder.permutation();
+        let main_local = row(&main, 0);
+        let main_next = row(&main, 1);
+        let preprocessed_local = row(&preprocessed, 0);
+        let preprocessed_next = row(&preprocessed, 1);
+        let z: AB::ExprEF = row(&permutation, 0)[column].into();
+        let z_next: AB::ExprEF = row(&permutation, 1)[column].into();
+
+        let product = |builder: &AB,
+                       tuples: &[Vec<VirtualPairCol<F>>],
+                       preprocessed: &[AB::Var],
+                       main: &[AB::Var]| {
+            tuples.iter().fold(one::<AB>(), |acc, tuple| {
+                let mut fingerprint = alpha.clone();
+                let mut power = beta.clone();
+                for field in tuple {
+                    let value = field.apply::<AB::Expr, AB::Var>(preprocessed, main);
+                    fingerprint = fingerprint + power.clone() * builder.lift(value);
+                    power = power * beta.clone();
+                }
+                acc * fingerprint
+            })
+        };
+        let left_local = product(builder, &self.left, &preprocessed_local, &main_local);
+        let right_local = product(builder, &self.right, &preprocessed_local, &main_local);
+        let left_next = product(builder, &self.left, &preprocessed_next, &main_next);
+        let right_next = product(builder, &self.right, &preprocessed_next, &main_next);
+
+        builder
+            .when_first_row()
+            .assert_eq_ext(z.clone() * right_local, left_local);
+        builder
+            .when_transition()
+            .assert_eq_ext(z_next * right_next, z.clone() * left_next);
+        builder.when_last_row().assert_eq_ext(z, one::<AB>());
+    }
+
+    /// Generates the running-product column from the main trace, the preprocessed trace if there
+    /// is one, and the challenges `alpha` and `beta`.
+    #[instrument(name = "generate grand product column", skip_all)]
+    pub fn generate_column<EF: ExtensionOf<F>>(
+        &self,
+        preprocessed: Option<&RowMajorMatrix<F>>,
+        main: &RowMajorMatrix<F>,
+        alpha: EF,
+        beta: EF,
+    ) -> Vec<EF> {
+        let product = |tuples: &[Vec<VirtualPairCol<F>>], preprocessed: &[F], main: &[F]| {
+            tuples.iter().fold(EF::one(), |acc, tuple| {
+                let mut fingerprint = alpha;
+                let mut power = beta;
+                for field in tuple {
+                    let value = field.apply::<F, F>(preprocessed, main);
+                    fingerprint = fingerprint + power * EF::from_base(value);
+                    power = power * beta;
+                }
+                acc * fingerprint
+            })
+        };
+
+        let mut z = EF::one();
+        (0..main.height())
+            .map(|r| {
+                let main_row = row(main, r);
+                let preprocessed_row =
+                    preprocessed.map_or(vec![], |preprocessed| row(preprocessed, r));
+                let left = product(&self.left, &preprocessed_row, &main_row);
+                let right = product(&self.right, &preprocessed_row, &main_row);
+                z = z * left * right.inv();
+                z
+            })
+            .collect()
+    }
+}
+
+fn one<AB: ExtensionBuilder>() -> AB::ExprEF {
+    AB::ExprEF::from(AB::EF::one())
+}
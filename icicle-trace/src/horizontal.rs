@@ -0,0 +1,410 @@
+//! Running two AIRs side by side on the same rows.
+//!
+//! `HorizontalAir` places the columns of its `right` AIR after those of its `left` AIR, in the
+//! main, preprocessed and auxiliary traces alike, and concatenates their periodic columns. Each AIR
+//! is evaluated on a `SubAirBuilder` which only shows it its own columns, so both run unchanged.
+//! Constraints between the two are supplied as a `HorizontalGlue`.
+
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use icicle_core::field::Field;
+use icicle_core::traits::Arithmetic;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+
+use crate::air::{
+    Air, AirBuilder, AirBuilderWithPublicValues, BaseAir, BaseAirWithPublicValues,
+    ExtensionBuilder, PairBuilder, PeriodicAirBuilder, PermutationAirBuilder,
+};
+use crate::extension::ExtensionOf;
+use crate::field_requirement::FieldRequirement;
+use crate::interaction::row;
+use crate::multi_phase::MultiPhaseAir;
+
+/// Constraints relating the columns of the two halves of a `HorizontalAir`.
+pub trait HorizontalGlue<AB: AirBuilder>: Sync {
+    /// `left` and `right` hold the window of rows of the respective AIR's main columns.
+    fn eval(
+        &self,
+        builder: &mut AB,
+        left: &RowMajorMatrix<AB::Var>,
+        right: &RowMajorMatrix<AB::Var>,
+    );
+}
+
+/// No constraints between the two halves.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct NoGlue;
+
+impl<AB: AirBuilder> HorizontalGlue<AB> for NoGlue {
+    fn eval(&self, _: &mut AB, _: &RowMajorMatrix<AB::Var>, _: &RowMajorMatrix<AB::Var>) {}
+}
+
+/// Two AIRs sharing rows, with the columns of `right` after those of `left`.
+///
+/// Both AIRs see all public values, and draw their challenges from the same list, so the composed
+/// AIR has as many of each as the AIR needing the most.
+#[derive(Clone, Debug)]
+pub struct HorizontalAir<A, B, G = NoGlue> {
+    pub left: A,
+    pub right: B,
+    pub glue: G,
+}
+
+impl<A, B> HorizontalAir<A, B> {
+    pub const fn new(left: A, right: B) -> Self {
+        Self {
+            left,
+            right,
+            glue: NoGlue,
+        }
+    }
+}
+
+impl<A, B, G> HorizontalAir<A, B, G> {
+    pub const fn with_glue(left: A, right: B, glue: G) -> Self {
+        Self { left, right, glue }
+    }
+
+    /// Joins a trace of `left` and a trace of `right` of the same height into one trace.
+    pub fn concat_traces<F>(
+        &self,
+        left: &RowMajorMatrix<F>,
+        right: &RowMajorMatrix<F>,
+    ) -> RowMajorMatrix<F>
+    where
+        F: Field + Arithmetic,
+        A: BaseAir<F>,
+        B: BaseAir<F>,
+    {
+        assert_eq!(
+            left.width(),
+            self.left.width(),
+            "left trace has the wrong width"
+        );
+        assert_eq!(
+            right.width(),
+            self.right.width(),
+            "right trace has the wrong width"
+        );
+        concat_columns(left, right)
+    }
+}
+
+impl<F, A, B, G> BaseAir<F> for HorizontalAir<A, B, G>
+where
+    F: Clone + Send + Sync,
+    A: BaseAir<F>,
+    B: BaseAir<F>,
+    G: Sync,
+{
+    fn width(&self) -> usize {
+        self.left.width() + self.right.width()
+    }
+
+    fn window_size(&self) -> usize {
+        self.left.window_size().max(self.right.window_size())
+    }
+
+    fn preprocessed_trace(&self) -> Option<RowMajorMatrix<F>> {
+        match (
+            self.left.preprocessed_trace(),
+            self.right.preprocessed_trace(),
+        ) {
+            (Some(left), Some(right)) => Some(concat_columns(&left, &right)),
+            (left, right) => left.or(right),
+        }
+    }
+
+    fn preprocessed_width(&self) -> usize {
+        self.left.preprocessed_width() + self.right.preprocessed_width()
+    }
+
+    fn permutation_width(&self) -> usize {
+        self.left.permutation_width() + self.right.permutation_width()
+    }
+
+    fn num_challenges(&self) -> usize {
+        self.left.num_challenges().max(self.right.num_challenges())
+    }
+
+    fn field_requirements(&self) -> Vec<FieldRequirement> {
+        let mut requirements = self.left.field_requirements();
+        requirements.extend(self.right.field_requirements());
+        requirements
+    }
+
+    fn periodic_columns(&self) -> Vec<Vec<F>> {
+        let mut columns = self.left.periodic_columns();
+        columns.extend(self.right.periodic_columns());
+        columns
+    }
+}
+
+impl<F, A, B, G> BaseAirWithPublicValues<F> for HorizontalAir<A, B, G>
+where
+    F: Field + Arithmetic,
+    A: BaseAirWithPublicValues<F>,
+    B: BaseAirWithPublicValues<F>,
+    G: Sync,
+{
+    fn num_public_values(&self) -> usize {
+        self.left
+            .num_public_values()
+            .max(self.right.num_public_values())
+    }
+}
+
+impl<F, EF, A, B, G> MultiPhaseAir<F, EF> for HorizontalAir<A, B, G>
+where
+    F: Field + Arithmetic,
+    EF: ExtensionOf<F>,
+    A: MultiPhaseAir<F, EF>,
+    B: MultiPhaseAir<F, EF>,
+    G: Sync,
+{
+    fn generate_permutation_trace(
+        &self,
+        main: &RowMajorMatrix<F>,
+        challenges: &[EF],
+    ) -> RowMajorMatrix<EF> {
+        let left_width = self.left.width();
+        let left = self.left.generate_permutation_trace(
+            &columns(main, 0..left_width),
+            &challenges[..self.left.num_challenges()],
+        );
+        let right = self.right.generate_permutation_trace(
+            &columns(main, left_width..main.width()),
+            &challenges[..self.right.num_challenges()],
+        );
+        concat_columns(&left, &right)
+    }
+}
+
+impl<AB, A, B, G> Air<AB> for HorizontalAir<A, B, G>
+where
+    AB: AirBuilder,
+    A: BaseAir<AB::F> + for<'a> Air<SubAirBuilder<'a, AB>>,
+    B: BaseAir<AB::F> + for<'a> Air<SubAirBuilder<'a, AB>>,
+    G: HorizontalGlue<AB>,
+{
+    fn eval(&self, builder: &mut AB) {
+        let left = SubAirLayout::of(&self.left);
+        let right = SubAirLayout::of(&self.right).after(&left);
+
+        builder.scope("left", |builder| {
+            self.left
+                .eval(&mut SubAirBuilder::new(builder, left.clone()));
+        });
+        builder.scope("right", |builder| {
+            self.right
+                .eval(&mut SubAirBuilder::new(builder, right.clone()));
+        });
+
+        let main = builder.main();
+        let left_main = columns(&main, left.main);
+        let right_main = columns(&main, right.main);
+        builder.scope("glue", |builder| {
+            self.glue.eval(builder, &left_main, &right_main);
+        });
+    }
+}
+
+/// Where the columns of one AIR sit in a composed AIR.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SubAirLayout {
+    pub main: Range<usize>,
+    pub preprocessed: Range<usize>,
+    pub permutation: Range<usize>,
+    pub periodic: Range<usize>,
+    pub num_challenges: usize,
+}
+
+impl SubAirLayout {
+    /// The layout of `air` placed at the start of every trace.
+    pub fn of<F, A: BaseAir<F>>(air: &A) -> Self {
+        Self {
+            main: 0..air.width(),
+            preprocessed: 0..air.preprocessed_width(),
+            permutation: 0..air.permutation_width(),
+            periodic: 0..air.periodic_columns().len(),
+            num_challenges: air.num_challenges(),
+        }
+    }
+
+    /// This layout moved to start after the columns of `previous`.
+    pub fn after(self, previous: &Self) -> Self {
+        let shift = |range: Range<usize>, by: usize| range.start + by..range.end + by;
+        Self {
+            main: shift(self.main, previous.main.end),
+            preprocessed: shift(self.preprocessed, previous.preprocessed.end),
+            permutation: shift(self.permutation, previous.permutation.end),
+            periodic: shift(self.periodic, previous.periodic.end),
+            num_challenges: self.num_challenges,
+        }
+    }
+}
+
+/// A builder which shows an AIR only the columns of a composed AIR given by a `SubAirLayout`, and
+/// passes its constraints on unchanged.
+#[derive(Debug)]
+pub struct SubAirBuilder<'a, AB: AirBuilder> {
+    inner: &'a mut AB,
+    layout: SubAirLayout,
+}
+
+impl<'a, AB: AirBuilder> SubAirBuilder<'a, AB> {
+    pub fn new(inner: &'a mut AB, layout: SubAirLayout) -> Self {
+        Self { inner, layout }
+    }
+}
+
+impl<AB: AirBuilder> AirBuilder for SubAirBuilder<'_, AB> {
+    type F = AB::F;
+    type Expr = AB::Expr;
+    type Var = AB::Var;
+    type M = RowMajorMatrix<AB::Var>;
+
+    fn main(&self) -> Self::M {
+        columns(&self.inner.main(), self.layout.main.clone())
+    }
+
+    fn is_first_row(&self) -> Self::Expr {
+        self.inner.is_first_row()
+    }
+
+    fn is_last_row(&self) -> Self::Expr {
+        self.inner.is_last_row()
+    }
+
+    fn is_transition(&self) -> Self::Expr {
+        self.inner.is_transition()
+    }
+
+    fn is_transition_window(&self, size: usize) -> Self::Expr {
+        self.inner.is_transition_window(size)
+    }
+
+    fn is_row(&self, row: usize) -> Self::Expr {
+        self.inner.is_row(row)
+    }
+
+    fn is_row_mod(&self, residue: usize, period: usize) -> Self::Expr {
+        self.inner.is_row_mod(residue, period)
+    }
+
+    fn is_segment_start(&self, len: usize) -> Self::Expr {
+        self.inner.is_segment_start(len)
+    }
+
+    fn zero(&self) -> Self::Expr {
+        self.inner.zero()
+    }
+
+    fn one(&self) -> Self::Expr {
+        self.inner.one()
+    }
+
+    fn two(&self) -> Self::Expr {
+        self.inner.two()
+    }
+
+    fn from_u32(&self, val: u32) -> Self::Expr {
+        self.inner.from_u32(val)
+    }
+
+    fn require_field(&mut self, requirement: FieldRequirement) {
+        self.inner.require_field(requirement);
+    }
+
+    fn push_scope(&mut self, name: &str) {
+        self.inner.push_scope(name);
+    }
+
+    fn pop_scope(&mut self) {
+        self.inner.pop_scope();
+    }
+
+    fn assert_zero<I: Into<Self::Expr>>(&mut self, x: I) {
+        self.inner.assert_zero(x);
+    }
+}
+
+impl<AB: AirBuilderWithPublicValues> AirBuilderWithPublicValues for SubAirBuilder<'_, AB> {
+    type PublicVar = AB::PublicVar;
+
+    fn public_values(&self) -> &[Self::PublicVar] {
+        self.inner.public_values()
+    }
+}
+
+impl<AB: PairBuilder> PairBuilder for SubAirBuilder<'_, AB> {
+    fn preprocessed(&self) -> Self::M {
+        columns(&self.inner.preprocessed(), self.layout.preprocessed.clone())
+    }
+}
+
+impl<AB: PeriodicAirBuilder> PeriodicAirBuilder for SubAirBuilder<'_, AB> {
+    fn periodic_values(&self) -> &[Self::Var] {
+        &self.inner.periodic_values()[self.layout.periodic.clone()]
+    }
+}
+
+impl<AB: ExtensionBuilder> ExtensionBuilder for SubAirBuilder<'_, AB> {
+    type EF = AB::EF;
+    type ExprEF = AB::ExprEF;
+    type VarEF = AB::VarEF;
+
+    fn lift<I: Into<Self::Expr>>(&self, x: I) -> Self::ExprEF {
+        self.inner.lift(x)
+    }
+
+    fn assert_zero_ext<I: Into<Self::ExprEF>>(&mut self, x: I) {
+        self.inner.assert_zero_ext(x);
+    }
+}
+
+impl<AB: PermutationAirBuilder> PermutationAirBuilder for SubAirBuilder<'_, AB> {
+    type MP = RowMajorMatrix<AB::VarEF>;
+    type RandomVar = AB::RandomVar;
+
+    fn permutation(&self) -> Self::MP {
+        columns(&self.inner.permutation(), self.layout.permutation.clone())
+    }
+
+    fn permutation_randomness(&self) -> &[Self::RandomVar] {
+        &self.inner.permutation_randomness()[..self.layout.num_challenges]
+    }
+}
+
+/// Copies the columns in `range` out of every row of `matrix`.
+pub fn columns<T: Clone + Send + Sync>(
+    matrix: &impl Matrix<T>,
+    range: Range<usize>,
+) -> RowMajorMatrix<T> {
+    let values = (0..matrix.height())
+        .flat_map(|r| row(matrix, r)[range.clone()].to_vec())
+        .collect();
+    RowMajorMatrix::new(values, range.len())
+}
+
+/// Places the columns of `right` after those of `left`, row by row.
+pub fn concat_columns<T: Clone + Send + Sync>(
+    left: &RowMajorMatrix<T>,
+    right: &RowMajorMatrix<T>,
+) -> RowMajorMatrix<T> {
+    let height = left.height().max(right.height());
+    assert!(
+        left.width() == 0 || right.width() == 0 || left.height() == right.height(),
+        "traces have different heights"
+    );
+    let values = (0..height)
+        .flat_map(|r| {
+            let mut joined = row(left, r);
+            joined.extend(row(right, r));
+            joined
+        })
+        .collect();
+    RowMajorMatrix::new(values, left.width() + right.width())
+}
@@ -0,0 +1,242 @@
+//! LogUp interactions between AIRs.
+//!
+//! An AIR sends tuples to, and receives tuples from, numbered buses. Each field of a tuple is a
+//! `VirtualPairCol` evaluated on the current row, and so is the multiplicity it is sent with.
+//! Across all AIRs sharing a bus, every tuple must be received as many times as it is sent.
+//!
+//! The argument draws two challenges, `alpha` and `beta`, and fingerprints a tuple `v` on bus `b`
+//! as `alpha + b + sum_j beta^(j + 1) v_j`. The auxiliary trace has one column per interaction,
+//! holding `multiplicity / fingerprint` for sends and `-multiplicity / fingerprint` for receives,
+//! followed by a column with the running sum of all of them. The final running sum is the AIR's
+//! contribution to its buses; the contributions of all AIRs must add up to zero.
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+use icicle_core::bignum::BigNum;
+use icicle_core::field::Field;
+use icicle_core::traits::Arithmetic;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use tracing::instrument;
+
+use crate::air::{BaseAir, ExtensionBuilder, PairBuilder, PermutationAirBuilder};
+use crate::extension::ExtensionOf;
+use crate::virtual_column::VirtualPairCol;
+
+/// The number of challenges the LogUp argument draws: `alpha` and `beta`.
+pub const LOGUP_NUM_CHALLENGES: usize = 2;
+
+/// The width of the auxiliary trace for `num_interactions` interactions.
+pub const fn logup_permutation_width(num_interactions: usize) -> usize {
+    num_interactions + 1
+}
+
+/// Whether an interaction adds tuples to its bus or removes them.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InteractionKind {
+    Send,
+    Receive,
+}
+
+/// A tuple sent to, or received from, a bus on every row of an AIR.
+#[derive(Clone, Debug)]
+pub struct Interaction<F: Field + Arithmetic> {
+    pub kind: InteractionKind,
+    pub bus: usize,
+    pub fields: Vec<VirtualPairCol<F>>,
+    pub multiplicity: VirtualPairCol<F>,
+}
+
+impl<F: Field + Arithmetic> Interaction<F> {
+    /// Sends `fields` to `bus`, `multiplicity` times per row.
+    pub fn send(bus: usize, fields: Vec<VirtualPairCol<F>>, multiplicity: VirtualPairCol<F>) -> Self {
+        Self {
+            kind: InteractionKind::Send,
+            bus,
+            fields,
+            multiplicity,
+        }
+    }
+
+    /// Receives `fields` from `bus`, `multiplicity` times per row.
+    pub fn receive(
+        bus: usize,
+        fields: Vec<VirtualPairCol<F>>,
+        multiplicity: VirtualPairCol<F>,
+    ) -> Self {
+        Self {
+            kind: InteractionKind::Receive,
+            bus,
+            fields,
+            multiplicity,
+        }
+    }
+
+    /// Evaluates the fields of this interaction on a row.
+    pub fn eval_fields(&self, preprocessed: &[F], main: &[F]) -> Vec<F> {
+        self.fields
+            .iter()
+            .map(|field| field.apply::<F, F>(preprocessed, main))
+            .collect()
+    }
+
+    /// Evaluates the multiplicity of this interaction on a row, negated for receives.
+    pub fn eval_signed_multiplicity(&self, preprocessed: &[F], main: &[F]) -> F {
+        let multiplicity = self.multiplicity.apply::<F, F>(preprocessed, main);
+        match self.kind {
+            InteractionKind::Send => multiplicity,
+            InteractionKind::Receive => F::zero() - multiplicity,
+        }
+    }
+}
+
+/// An AIR which takes part in LogUp interactions.
+///
+/// Such an AIR should report `logup_permutation_width(self.interactions().len())` and
+/// `LOGUP_NUM_CHALLENGES` from `BaseAir`, call `eval_logup` from `eval`, and generate its
+/// auxiliary trace with `generate_logup_trace`.
+pub trait InteractionAir<F: Field + Arithmetic>: BaseAir<F> {
+    fn interactions(&self) -> Vec<Interaction<F>>;
+}
+
+/// Constrains the LogUp auxiliary columns for `interactions`, and returns the running sum on the
+/// current row, which on the last row is the AIR's contribution to its buses.
+///
+/// If `expected_sum` is given, the final running sum must equal it. Otherwise it is left for the
+/// caller to constrain on the last row against the other AIRs on the same buses; the concrete value
+/// of a generated trace is `logup_cumulative_sum`.
+pub fn eval_logup<AB>(
+    builder: &mut AB,
+    interactions: &[Interaction<AB::F>],
+    expected_sum: Option<AB::ExprEF>,
+) -> AB::ExprEF
+where
+    AB: PermutationAirBuilder + PairBuilder,
+    AB::F: Arithmetic,
+{
+    let num_interactions = interactions.len();
+    let main = builder.main();
+    let preprocessed = builder.preprocessed();
+    let permutation = builder.permutation();
+    let main_local = row(&main, 0);
+    let preprocessed_local = row(&preprocessed, 0);
+    let permutation_local = row(&permutation, 0);
+    let permutation_next = row(&permutation, 1);
+    assert_eq!(
+        permutation_local.len(),
+        logup_permutation_width(num_interactions),
+        "permutation trace has the wrong width"
+    );
+
+    let randomness = builder.permutation_randomness();
+    let alpha: AB::ExprEF = randomness[0].into();
+    let beta: AB::ExprEF = randomness[1].into();
+
+    for (interaction, h) in interactions.iter().zip(permutation_local.iter()) {
+        let mut fingerprint = alpha.clone() + builder.lift(builder.from_u32(interaction.bus as u32));
+        let mut power = beta.clone();
+        for field in interaction.fields.iter() {
+            let value = field.apply::<AB::Expr, AB::Var>(&preprocessed_local, &main_local);
+            fingerprint = fingerprint + power.clone() * builder.lift(value);
+            power = power * beta.clone();
+        }
+
+        let multiplicity = builder.lift(
+            interaction
+                .multiplicity
+                .apply::<AB::Expr, AB::Var>(&preprocessed_local, &main_local),
+        );
+        let signed_multiplicity = match interaction.kind {
+            InteractionKind::Send => multiplicity,
+            InteractionKind::Receive => zero::<AB>() - multiplicity,
+        };
+        builder.assert_eq_ext((*h).into() * fingerprint, signed_multiplicity);
+    }
+
+    let sum_of = |terms: &[AB::VarEF]| {
+        terms
+            .iter()
+            .fold(zero::<AB>(), |acc, &term| acc + term.into())
+    };
+    let sum_local: AB::ExprEF = permutation_local[num_interactions].into();
+    let sum_next: AB::ExprEF = permutation_next[num_interactions].into();
+    let terms_local = sum_of(&permutation_local[..num_interactions]);
+    let terms_next = sum_of(&permutation_next[..num_interactions]);
+
+    builder
+        .when_first_row()
+        .assert_eq_ext(sum_local.clone(), terms_local);
+    builder
+        .when_transition()
+        .assert_eq_ext(sum_next, sum_local.clone() + terms_next);
+    if let Some(expected_sum) = expected_sum {
+        builder
+            .when_last_row()
+            .assert_eq_ext(sum_local.clone(), expected_sum);
+    }
+    sum_local
+}
+
+/// Generates the LogUp auxiliary trace for `interactions` from the main trace, the preprocessed
+/// trace if there is one, and the `LOGUP_NUM_CHALLENGES` challenges.
+#[instrument(name = "generate LogUp trace", skip_all)]
+pub fn generate_logup_trace<F, EF>(
+    interactions: &[Interaction<F>],
+    preprocessed: Option<&RowMajorMatrix<F>>,
+    main: &RowMajorMatrix<F>,
+    challenges: &[EF],
+) -> RowMajorMatrix<EF>
+where
+    F: Field + Arithmetic,
+    EF: ExtensionOf<F>,
+{
+    assert_eq!(
+        challenges.len(),
+        LOGUP_NUM_CHALLENGES,
+        "LogUp takes exactly two challenges"
+    );
+    let (alpha, beta) = (challenges[0], challenges[1]);
+    let width = logup_permutation_width(interactions.len());
+
+    let mut values = Vec::with_capacity(main.height() * width);
+    let mut running_sum = EF::zero();
+    for r in 0..main.height() {
+        let main_row = row(main, r);
+        let preprocessed_row = preprocessed.map_or(vec![], |preprocessed| row(preprocessed, r));
+
+        for interaction in interactions {
+            let mut fingerprint = alpha + EF::from_base(F::from_u32(interaction.bus as u32));
+            let mut power = beta;
+            for value in interaction.eval_fields(&preprocessed_row, &main_row) {
+                fingerprint = fingerprint + power * EF::from_base(value);
+                power = power * beta;
+            }
+            let multiplicity = interaction.eval_signed_multiplicity(&preprocessed_row, &main_row);
+            let h = EF::from_base(multiplicity) * fingerprint.inv();
+            running_sum = running_sum + h;
+            values.push(h);
+        }
+        values.push(running_sum);
+    }
+
+    RowMajorMatrix::new(values, width)
+}
+
+/// The final running sum of a LogUp auxiliary trace, i.e. the AIR's contribution to its buses.
+pub fn logup_cumulative_sum<EF: Clone + Send + Sync>(permutation: &RowMajorMatrix<EF>) -> EF {
+    let last = row(permutation, permutation.height() - 1);
+    last[last.len() - 1].clone()
+}
+
+fn zero<AB: ExtensionBuilder>() -> AB::ExprEF {
+    AB::ExprEF::from(AB::EF::zero())
+}
+
+/// Copies row `r` of `matrix`, treating a matrix without columns as having empty rows.
+pub(crate) fn row<T: Clone + Send + Sync, M: Matrix<T>>(matrix: &M, r: usize) -> Vec<T> {
+    if matrix.width() == 0 {
+        return vec![];
+    }
+    matrix.row_slice(r).expect("row_slice returned None").to_vec()
+}
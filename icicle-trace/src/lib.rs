@@ -7,10 +7,27 @@
 #![no_std]
 
 extern crate alloc;
+// Lets `#[derive(AirColumns)]` refer to this crate as `::icicle_trace` from within it.
+extern crate self as icicle_trace;
 
 pub mod air;
 pub mod check_constraints;
+pub mod columns;
+pub mod config;
+pub mod dynamic;
+pub mod extension;
+pub mod field_requirement;
+pub mod grand_product;
+pub mod horizontal;
+pub mod interaction;
+pub mod multi_phase;
+pub mod padded;
+pub mod parser;
+pub mod range_check;
+pub mod trace_view;
 pub mod utils;
+pub mod vertical;
+pub mod word;
 mod virtual_column;
 
 pub mod symbolic_builder;
@@ -18,10 +35,22 @@ pub mod symbolic_expression;
 pub mod symbolic_variable;
 
 pub use air::*;
+pub use check_constraints::*;
+pub use columns::AirColumns;
+pub use config::*;
+pub use dynamic::*;
+pub use extension::*;
+pub use field_requirement::*;
+pub use grand_product::*;
+pub use horizontal::*;
+pub use interaction::*;
+pub use multi_phase::*;
+pub use padded::*;
+pub use parser::*;
+pub use range_check::*;
 pub use symbolic_builder::*;
 pub use symbolic_expression::*;
 pub use symbolic_variable::*;
+pub use trace_view::*;
+pub use vertical::*;
 pub use virtual_column::*;
-
-#[cfg(debug_assertions)]
-pub use check_constraints::*;
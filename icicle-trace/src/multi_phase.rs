@@ -0,0 +1,182 @@
+//! Multi-phase trace generation.
+//!
+//! An AIR first generates its main trace, then receives verifier challenges, and finally generates
+//! an auxiliary (permutation) trace over `EF` from the main trace and those challenges.
+
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+use icicle_core::bignum::BigNum;
+use icicle_core::field::Field;
+use icicle_core::traits::Arithmetic;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use tracing::instrument;
+
+use crate::air::{Air, BaseAir, BaseAirWithPublicValues};
+use crate::check_constraints::{
+    check_constraints_with_permutation, ConstraintError, DebugConstraintBuilder,
+};
+use crate::extension::ExtensionOf;
+
+/// A source of verifier challenges.
+pub trait ChallengeSource<EF> {
+    fn sample(&mut self) -> EF;
+
+    fn sample_vec(&mut self, n: usize) -> Vec<EF> {
+        (0..n).map(|_| self.sample()).collect()
+    }
+}
+
+/// Challenges drawn from a seeded PRNG, useful for tests and debugging.
+#[derive(Debug)]
+pub struct SeededChallenger<F> {
+    rng: StdRng,
+    _phantom: PhantomData<F>,
+}
+
+impl<F> SeededChallenger<F> {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            rng: StdRng::seed_from_u64(seed),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<F: Field + Arithmetic, EF: ExtensionOf<F>> ChallengeSource<EF> for SeededChallenger<F> {
+    fn sample(&mut self) -> EF {
+        let coeffs: Vec<F> = (0..EF::DEGREE)
+            .map(|_| {
+                // Both halves are below 2^16, so this is a valid reduction of a random u32 as long
+                // as the characteristic exceeds 2^16.
+                let val: u32 = self.rng.random();
+                F::from_u32(val & 0xFFFF) + F::from_u32(val >> 16) * F::from_u32(1 << 16)
+            })
+            .collect();
+        EF::from_base_slice(&coeffs)
+    }
+}
+
+/// Challenges supplied by the caller, e.g. taken from a transcript.
+#[derive(Clone, Debug)]
+pub struct FixedChallenges<EF> {
+    challenges: Vec<EF>,
+    next: usize,
+}
+
+impl<EF> FixedChallenges<EF> {
+    pub const fn new(challenges: Vec<EF>) -> Self {
+        Self {
+            challenges,
+            next: 0,
+        }
+    }
+}
+
+impl<EF: Copy> ChallengeSource<EF> for FixedChallenges<EF> {
+    fn sample(&mut self) -> EF {
+        let challenge = *self
+            .challenges
+            .get(self.next)
+            .expect("ran out of fixed challenges");
+        self.next += 1;
+        challenge
+    }
+}
+
+/// An AIR whose trace is generated in two phases, with verifier challenges in between.
+///
+/// The number of challenges and the auxiliary trace width are reported through
+/// `BaseAir::num_challenges` and `BaseAir::permutation_width`.
+pub trait MultiPhaseAir<F: Field + Arithmetic, EF: ExtensionOf<F>>: BaseAir<F> {
+    /// Generates the auxiliary trace from the main trace and the verifier challenges.
+    fn generate_permutation_trace(
+        &self,
+        main: &RowMajorMatrix<F>,
+        challenges: &[EF],
+    ) -> RowMajorMatrix<EF>;
+}
+
+/// The output of every phase of trace generation.
+#[derive(Clone, Debug)]
+pub struct MultiPhaseTrace<F, EF> {
+    pub main: RowMajorMatrix<F>,
+    pub challenges: Vec<EF>,
+    pub permutation: RowMajorMatrix<EF>,
+}
+
+/// Samples the challenges for `air` and generates its auxiliary trace on top of `main`.
+#[instrument(name = "generate multi-phase trace", skip_all)]
+pub fn generate_multi_phase_trace<F, EF, A, C>(
+    air: &A,
+    main: RowMajorMatrix<F>,
+    challenger: &mut C,
+) -> MultiPhaseTrace<F, EF>
+where
+    F: Field + Arithmetic,
+    EF: ExtensionOf<F>,
+    A: MultiPhaseAir<F, EF>,
+    C: ChallengeSource<EF>,
+{
+    assert_eq!(main.width(), air.width(), "main trace has the wrong width");
+
+    let challenges = challenger.sample_vec(air.num_challenges());
+    let permutation = air.generate_permutation_trace(&main, &challenges);
+    assert_eq!(
+        permutation.width(),
+        air.permutation_width(),
+        "permutation trace has the wrong width"
+    );
+    assert_eq!(
+        permutation.height(),
+        main.height(),
+        "permutation trace height does not match the main trace"
+    );
+
+    MultiPhaseTrace {
+        main,
+        challenges,
+        permutation,
+    }
+}
+
+/// Checks the constraints of `air` on every phase of `trace`, returning the first one that fails.
+pub fn try_check_multi_phase_constraints<F, EF, A>(
+    air: &A,
+    trace: &MultiPhaseTrace<F, EF>,
+    public_values: &[F],
+) -> Result<(), ConstraintError<F, EF>>
+where
+    F: Field + Arithmetic,
+    EF: ExtensionOf<F>,
+    A: MultiPhaseAir<F, EF>
+        + BaseAirWithPublicValues<F>
+        + for<'a> Air<DebugConstraintBuilder<'a, F, EF>>,
+{
+    check_constraints_with_permutation(
+        air,
+        &trace.main,
+        &trace.permutation,
+        &trace.challenges,
+        public_values,
+    )
+}
+
+/// Like `try_check_multi_phase_constraints`, but panics with the error if a check fails.
+pub fn check_multi_phase_constraints<F, EF, A>(
+    air: &A,
+    trace: &MultiPhaseTrace<F, EF>,
+    public_values: &[F],
+) where
+    F: Field + Arithmetic,
+    EF: ExtensionOf<F>,
+    A: MultiPhaseAir<F, EF>
+        + BaseAirWithPublicValues<F>
+        + for<'a> Air<DebugConstraintBuilder<'a, F, EF>>,
+{
+    try_check_multi_phase_constraints(air, trace, public_values)
+        .unwrap_or_else(|err| panic!("{}", err));
+}
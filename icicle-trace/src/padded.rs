@@ -0,0 +1,314 @@
+//! Padding any AIR to a power-of-two height with an `is_real` column.
+//!
+//! `PaddedAir` appends a column to the wrapped AIR's main trace which is one on real rows and zero
+//! on padding rows, with all real rows first and the first row real. The wrapped AIR's constraints
+//! are multiplied by `is_real`, its transitions are only enforced between two real rows, and its
+//! last-row constraints apply to the last real row. Padding rows are therefore unconstrained and
+//! can be filled with zeros instead of running the computation on dummy inputs.
+
+use alloc::vec::Vec;
+
+use icicle_core::field::Field;
+use icicle_core::traits::Arithmetic;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+
+use crate::air::{
+    Air, AirBuilder, AirBuilderWithPublicValues, BaseAir, BaseAirWithPublicValues,
+    ExtensionBuilder, PairBuilder, PeriodicAirBuilder, PermutationAirBuilder,
+};
+use crate::extension::ExtensionOf;
+use crate::field_requirement::FieldRequirement;
+use crate::interaction::row;
+use crate::multi_phase::MultiPhaseAir;
+
+/// Wraps an AIR so that its trace can be padded with rows which do not satisfy its constraints.
+///
+/// The `is_real` column comes after the wrapped AIR's columns. Gating raises the degree of the
+/// wrapped AIR's constraints by one, that of its transitions by two, and that of its last-row
+/// constraints by three.
+#[derive(Clone, Debug)]
+pub struct PaddedAir<A> {
+    pub inner: A,
+}
+
+impl<A> PaddedAir<A> {
+    pub const fn new(inner: A) -> Self {
+        Self { inner }
+    }
+
+    /// Appends the `is_real` column to a trace of the wrapped AIR, and pads it with zero rows up
+    /// to the next power of two.
+    ///
+    /// The trace must have at least one row, as a trace of padding rows only is rejected.
+    pub fn pad_trace<F>(&self, real: RowMajorMatrix<F>) -> RowMajorMatrix<F>
+    where
+        F: Field + Arithmetic,
+        A: BaseAir<F>,
+    {
+        let width = self.inner.width();
+        assert_eq!(real.width(), width, "trace has the wrong width");
+        let num_real = real.height();
+        let height = num_real.max(1).next_power_of_two();
+
+        let mut values = Vec::with_capacity(height * (width + 1));
+        for r in 0..num_real {
+            values.extend(row(&real, r));
+            values.push(F::one());
+        }
+        values.resize(height * (width + 1), F::zero());
+        RowMajorMatrix::new(values, width + 1)
+    }
+
+    /// Removes the `is_real` column and the padding rows from a padded trace.
+    pub fn unpad_trace<F>(&self, padded: &RowMajorMatrix<F>) -> RowMajorMatrix<F>
+    where
+        F: Field + Arithmetic,
+        A: BaseAir<F>,
+    {
+        let width = self.inner.width();
+        let mut values = Vec::new();
+        for r in 0..padded.height() {
+            let padded_row = row(padded, r);
+            if padded_row[width] == F::zero() {
+                break;
+            }
+            values.extend_from_slice(&padded_row[..width]);
+        }
+        RowMajorMatrix::new(values, width)
+    }
+}
+
+impl<F, A: BaseAir<F>> BaseAir<F> for PaddedAir<A> {
+    fn width(&self) -> usize {
+        self.inner.width() + 1
+    }
+
+    fn window_size(&self) -> usize {
+        self.inner.window_size()
+    }
+
+    fn preprocessed_trace(&self) -> Option<RowMajorMatrix<F>> {
+        self.inner.preprocessed_trace()
+    }
+
+    fn preprocessed_width(&self) -> usize {
+        self.inner.preprocessed_width()
+    }
+
+    fn permutation_width(&self) -> usize {
+        self.inner.permutation_width()
+    }
+
+    fn num_challenges(&self) -> usize {
+        self.inner.num_challenges()
+    }
+
+    fn field_requirements(&self) -> Vec<FieldRequirement> {
+        self.inner.field_requirements()
+    }
+
+    fn periodic_columns(&self) -> Vec<Vec<F>> {
+        self.inner.periodic_columns()
+    }
+}
+
+impl<F: Field + Arithmetic, A: BaseAirWithPublicValues<F>> BaseAirWithPublicValues<F>
+    for PaddedAir<A>
+{
+    fn num_public_values(&self) -> usize {
+        self.inner.num_public_values()
+    }
+}
+
+impl<F, EF, A> MultiPhaseAir<F, EF> for PaddedAir<A>
+where
+    F: Field + Arithmetic,
+    EF: ExtensionOf<F>,
+    A: MultiPhaseAir<F, EF>,
+{
+    fn generate_permutation_trace(
+        &self,
+        main: &RowMajorMatrix<F>,
+        challenges: &[EF],
+    ) -> RowMajorMatrix<EF> {
+        let width = self.inner.width();
+        let values = (0..main.height())
+            .flat_map(|r| row(main, r).into_iter().take(width))
+            .collect();
+        self.inner
+            .generate_permutation_trace(&RowMajorMatrix::new(values, width), challenges)
+    }
+}
+
+impl<AB, A> Air<AB> for PaddedAir<A>
+where
+    AB: AirBuilder,
+    A: BaseAir<AB::F> + for<'a> Air<PaddedAirBuilder<'a, AB>>,
+{
+    fn eval(&self, builder: &mut AB) {
+        let width = self.inner.width();
+        let main = builder.main();
+        let rows: Vec<_> = (0..main.height()).map(|r| row(&main, r)).collect();
+        let is_real: Vec<_> = rows.iter().map(|r| r[width]).collect();
+        let inner_main = RowMajorMatrix::new(
+            rows.iter()
+                .flat_map(|r| r[..width].iter().copied())
+                .collect(),
+            width,
+        );
+
+        builder.assert_bool(is_real[0]);
+        // Without a real row every wrapped constraint, public value bindings included, is off.
+        builder.when_first_row().assert_one(is_real[0]);
+        let becomes_real = (builder.one() - is_real[0].into()) * is_real[1].into();
+        builder.when_transition().assert_zero(becomes_real);
+
+        self.inner.eval(&mut PaddedAirBuilder {
+            inner: builder,
+            main: inner_main,
+            is_real,
+        });
+    }
+}
+
+/// The builder `PaddedAir` evaluates the wrapped AIR with: it hides the `is_real` column and gates
+/// every constraint by it.
+#[derive(Debug)]
+pub struct PaddedAirBuilder<'a, AB: AirBuilder> {
+    inner: &'a mut AB,
+    main: RowMajorMatrix<AB::Var>,
+    /// The `is_real` value of each row in the window.
+    is_real: Vec<AB::Var>,
+}
+
+impl<AB: AirBuilder> PaddedAirBuilder<'_, AB> {
+    pub fn is_real(&self) -> AB::Expr {
+        self.is_real[0].into()
+    }
+
+    /// Copies a matrix of the underlying builder, so that it has the same type as `main`.
+    fn copy(matrix: &impl Matrix<AB::Var>) -> RowMajorMatrix<AB::Var> {
+        let values = (0..matrix.height()).flat_map(|r| row(matrix, r)).collect();
+        RowMajorMatrix::new(values, matrix.width())
+    }
+}
+
+impl<AB: AirBuilder> AirBuilder for PaddedAirBuilder<'_, AB> {
+    type F = AB::F;
+    type Expr = AB::Expr;
+    type Var = AB::Var;
+    type M = RowMajorMatrix<AB::Var>;
+
+    fn main(&self) -> Self::M {
+        self.main.clone()
+    }
+
+    fn is_first_row(&self) -> Self::Expr {
+        self.inner.is_first_row()
+    }
+
+    /// The last real row: either followed by a padding row, or the last row of the trace.
+    fn is_last_row(&self) -> Self::Expr {
+        let is_real_next: AB::Expr = self.is_real[1].into();
+        self.inner.one() - is_real_next * (self.inner.one() - self.inner.is_last_row())
+    }
+
+    /// Windows which lie entirely within the real rows. As real rows come first, it is enough to
+    /// check the last row of the window.
+    fn is_transition_window(&self, size: usize) -> Self::Expr {
+        self.inner.is_transition_window(size) * self.is_real[size - 1].into()
+    }
+
+    fn is_row(&self, row: usize) -> Self::Expr {
+        self.inner.is_row(row)
+    }
+
+    fn is_row_mod(&self, residue: usize, period: usize) -> Self::Expr {
+        self.inner.is_row_mod(residue, period)
+    }
+
+    fn is_segment_start(&self, len: usize) -> Self::Expr {
+        self.inner.is_segment_start(len)
+    }
+
+    fn zero(&self) -> Self::Expr {
+        self.inner.zero()
+    }
+
+    fn one(&self) -> Self::Expr {
+        self.inner.one()
+    }
+
+    fn two(&self) -> Self::Expr {
+        self.inner.two()
+    }
+
+    fn from_u32(&self, val: u32) -> Self::Expr {
+        self.inner.from_u32(val)
+    }
+
+    fn require_field(&mut self, requirement: FieldRequirement) {
+        self.inner.require_field(requirement);
+    }
+
+    fn push_scope(&mut self, name: &str) {
+        self.inner.push_scope(name);
+    }
+
+    fn pop_scope(&mut self) {
+        self.inner.pop_scope();
+    }
+
+    fn assert_zero<I: Into<Self::Expr>>(&mut self, x: I) {
+        self.inner.assert_zero(self.is_real() * x.into());
+    }
+}
+
+impl<AB: AirBuilderWithPublicValues> AirBuilderWithPublicValues for PaddedAirBuilder<'_, AB> {
+    type PublicVar = AB::PublicVar;
+
+    fn public_values(&self) -> &[Self::PublicVar] {
+        self.inner.public_values()
+    }
+}
+
+impl<AB: PairBuilder> PairBuilder for PaddedAirBuilder<'_, AB> {
+    fn preprocessed(&self) -> Self::M {
+        Self::copy(&self.inner.preprocessed())
+    }
+}
+
+impl<AB: PeriodicAirBuilder> PeriodicAirBuilder for PaddedAirBuilder<'_, AB> {
+    fn periodic_values(&self) -> &[Self::Var] {
+        self.inner.periodic_values()
+    }
+}
+
+impl<AB: ExtensionBuilder> ExtensionBuilder for PaddedAirBuilder<'_, AB> {
+    type EF = AB::EF;
+    type ExprEF = AB::ExprEF;
+    type VarEF = AB::VarEF;
+
+    fn lift<I: Into<Self::Expr>>(&self, x: I) -> Self::ExprEF {
+        self.inner.lift(x)
+    }
+
+    fn assert_zero_ext<I: Into<Self::ExprEF>>(&mut self, x: I) {
+        let is_real = self.inner.lift(self.is_real());
+        self.inner.assert_zero_ext(is_real * x.into());
+    }
+}
+
+impl<AB: PermutationAirBuilder> PermutationAirBuilder for PaddedAirBuilder<'_, AB> {
+    type MP = AB::MP;
+    type RandomVar = AB::RandomVar;
+
+    fn permutation(&self) -> Self::MP {
+        self.inner.permutation()
+    }
+
+    fn permutation_randomness(&self) -> &[Self::RandomVar] {
+        self.inner.permutation_randomness()
+    }
+}
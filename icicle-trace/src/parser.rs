@@ -0,0 +1,444 @@
+//! A small text language for writing `DynamicAir`s.
+//!
+//! A program is a list of statements separated by `;`:
+//!
+//! ```text
+//! col a, b;
+//! first: a = pub[0];
+//! transition: b' = a + b;
+//! last: b = pub[1]
+//! ```
+//!
+//! `col` declares main columns, which must be declared before they are used. Every other statement
+//! is a constraint `lhs = rhs`, or `expr` alone for `expr = 0`, optionally preceded by `first:`,
+//! `last:` or `transition:` to restrict it to the first row, last row, or rows where the whole
+//! window fits. Expressions are built from columns, where each `'` moves to the next row, public
+//! values `pub[i]`, integer constants, `+`, `-`, `*`, parentheses, and `^` with a constant exponent
+//! of at most 64. Parentheses and negations nest at most 64 deep, and each constraint may have at
+//! most 4096 nodes once its powers are expanded. The number of public values is one more than the
+//! largest index used.
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt::{self, Display, Formatter};
+
+use icicle_core::field::Field;
+use icicle_core::traits::Arithmetic;
+
+use crate::dynamic::DynamicAir;
+use crate::symbolic_expression::SymbolicExpression;
+use crate::symbolic_variable::{Entry, SymbolicVariable};
+
+/// The largest exponent `^` accepts. Powers are expanded into products, so this bounds their size.
+const MAX_EXPONENT: u64 = 64;
+
+/// How deeply parentheses and negations may nest, which bounds the parser's recursion.
+const MAX_NESTING: usize = 64;
+
+/// The most nodes a constraint may have once its powers are expanded. `DynamicAir` evaluates every
+/// node on every row, and nested powers multiply their sizes, so `MAX_EXPONENT` alone does not
+/// bound this.
+const MAX_CONSTRAINT_SIZE: usize = 4096;
+
+/// Returned when a program cannot be parsed, pointing at the offending token.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    /// One-based line of the error.
+    pub line: usize,
+    /// One-based column of the error, in characters.
+    pub column: usize,
+    pub message: String,
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.message
+        )
+    }
+}
+
+impl core::error::Error for ParseError {}
+
+/// Parses a program in the language described in this module into a `DynamicAir`.
+pub fn parse_air<F: Field + Arithmetic>(source: &str) -> Result<DynamicAir<F>, ParseError> {
+    let tokens = tokenize(source)?;
+    let mut parser = Parser {
+        source,
+        tokens,
+        position: 0,
+        columns: Vec::new(),
+        num_public_values: 0,
+        nesting: 0,
+        size: 0,
+    };
+
+    let mut constraints = Vec::new();
+    loop {
+        match parser.peek() {
+            Token::End => break,
+            Token::Symbol(';') => {
+                parser.position += 1;
+                continue;
+            }
+            _ => {}
+        }
+        if let Some(constraint) = parser.statement()? {
+            constraints.push(constraint);
+        }
+        match parser.peek() {
+            Token::End => break,
+            Token::Symbol(';') => parser.position += 1,
+            _ => return Err(parser.error("expected `;` between statements")),
+        }
+    }
+
+    let mut air = DynamicAir::new(parser.columns, parser.num_public_values);
+    for constraint in constraints {
+        air.assert_zero(constraint);
+    }
+    Ok(air)
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Ident(String),
+    Number(u64),
+    Symbol(char),
+    End,
+}
+
+fn tokenize(source: &str) -> Result<Vec<(Token, usize)>, ParseError> {
+    let mut tokens = Vec::new();
+    let mut chars = source.char_indices().peekable();
+    while let Some(&(start, c)) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c == '#' {
+            while chars.next_if(|&(_, c)| c != '\n').is_some() {}
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            let mut ident = String::new();
+            while let Some((_, c)) = chars.next_if(|&(_, c)| c.is_ascii_alphanumeric() || c == '_')
+            {
+                ident.push(c);
+            }
+            tokens.push((Token::Ident(ident), start));
+        } else if c.is_ascii_digit() {
+            let mut value = 0u64;
+            while let Some((_, c)) = chars.next_if(|&(_, c)| c.is_ascii_digit()) {
+                value = value
+                    .checked_mul(10)
+                    .and_then(|value| value.checked_add(u64::from(c as u8 - b'0')))
+                    .ok_or_else(|| error_at(source, start, "number is too large"))?;
+            }
+            tokens.push((Token::Number(value), start));
+        } else if ",;:=+-*^()[]'".contains(c) {
+            chars.next();
+            tokens.push((Token::Symbol(c), start));
+        } else {
+            return Err(error_at(
+                source,
+                start,
+                &format!("unexpected character `{}`", c),
+            ));
+        }
+    }
+    tokens.push((Token::End, source.len()));
+    Ok(tokens)
+}
+
+fn error_at(source: &str, offset: usize, message: &str) -> ParseError {
+    let before = &source[..offset];
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    ParseError {
+        line: before.matches('\n').count() + 1,
+        column: before[line_start..].chars().count() + 1,
+        message: message.to_string(),
+    }
+}
+
+struct Parser<'a> {
+    source: &'a str,
+    tokens: Vec<(Token, usize)>,
+    position: usize,
+    columns: Vec<String>,
+    num_public_values: usize,
+    /// The parentheses and negations around the current token.
+    nesting: usize,
+    /// The nodes of the current constraint so far.
+    size: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> &Token {
+        &self.tokens[self.position].0
+    }
+
+    fn error(&self, message: &str) -> ParseError {
+        error_at(self.source, self.tokens[self.position].1, message)
+    }
+
+    fn expect(&mut self, symbol: char) -> Result<(), ParseError> {
+        if *self.peek() == Token::Symbol(symbol) {
+            self.position += 1;
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected `{}`", symbol)))
+        }
+    }
+
+    /// Parses with `parse` one level deeper, failing if that nests too deeply.
+    fn nested<T>(
+        &mut self,
+        parse: impl FnOnce(&mut Self) -> Result<T, ParseError>,
+    ) -> Result<T, ParseError> {
+        if self.nesting == MAX_NESTING {
+            return Err(self.error(&format!(
+                "parentheses and negations may nest at most {} deep",
+                MAX_NESTING
+            )));
+        }
+        self.nesting += 1;
+        let result = parse(self);
+        self.nesting -= 1;
+        result
+    }
+
+    /// Counts `nodes` more nodes of the current constraint, failing if it grows too large.
+    fn grow(&mut self, nodes: usize) -> Result<(), ParseError> {
+        self.size = self.size.saturating_add(nodes);
+        if self.size > MAX_CONSTRAINT_SIZE {
+            return Err(self.error(&format!(
+                "constraints may have at most {} nodes once powers are expanded",
+                MAX_CONSTRAINT_SIZE
+            )));
+        }
+        Ok(())
+    }
+
+    fn ident(&mut self) -> Result<String, ParseError> {
+        match self.peek().clone() {
+            Token::Ident(name) if name != "col" && name != "pub" => {
+                self.position += 1;
+                Ok(name)
+            }
+            _ => Err(self.error("expected a column name")),
+        }
+    }
+
+    fn number(&mut self) -> Result<u64, ParseError> {
+        match *self.peek() {
+            Token::Number(value) => {
+                self.position += 1;
+                Ok(value)
+            }
+            _ => Err(self.error("expected a number")),
+        }
+    }
+
+    /// A column declaration, which yields no constraint, or a constraint.
+    fn statement<F: Field + Arithmetic>(
+        &mut self,
+    ) -> Result<Option<SymbolicExpression<F>>, ParseError> {
+        if *self.peek() == Token::Ident("col".to_string()) {
+            self.position += 1;
+            loop {
+                let name = self.ident()?;
+                if self.columns.contains(&name) {
+                    self.position -= 1;
+                    return Err(self.error(&format!("column `{}` is declared twice", name)));
+                }
+                self.columns.push(name);
+                if *self.peek() != Token::Symbol(',') {
+                    return Ok(None);
+                }
+                self.position += 1;
+            }
+        }
+
+        let selector = match (self.peek().clone(), &self.tokens[self.position + 1].0) {
+            (Token::Ident(name), Token::Symbol(':')) => {
+                let selector = match name.as_str() {
+                    "first" => Selector::FirstRow,
+                    "last" => Selector::LastRow,
+                    "transition" => Selector::Transition,
+                    _ => {
+                        return Err(self.error(&format!(
+                            "unknown selector `{}`, expected `first`, `last` or `transition`",
+                            name
+                        )))
+                    }
+                };
+                self.position += 2;
+                Some(selector)
+            }
+            _ => None,
+        };
+
+        self.size = 0;
+        let lhs = self.expression()?;
+        let constraint = if *self.peek() == Token::Symbol('=') {
+            self.position += 1;
+            self.grow(1)?;
+            lhs - self.expression()?
+        } else {
+            lhs
+        };
+        Ok(Some(match selector {
+            None => constraint,
+            Some(Selector::FirstRow) => SymbolicExpression::IsFirstRow * constraint,
+            Some(Selector::LastRow) => SymbolicExpression::IsLastRow * constraint,
+            Some(Selector::Transition) => match max_offset(&constraint) {
+                0 | 1 => SymbolicExpression::IsTransition * constraint,
+                offset => SymbolicExpression::IsTransitionWindow(offset + 1) * constraint,
+            },
+        }))
+    }
+
+    /// `term (('+' | '-') term)*`
+    fn expression<F: Field + Arithmetic>(&mut self) -> Result<SymbolicExpression<F>, ParseError> {
+        let mut expression = self.term()?;
+        loop {
+            match self.peek() {
+                Token::Symbol('+') => {
+                    self.position += 1;
+                    self.grow(1)?;
+                    expression += self.term()?;
+                }
+                Token::Symbol('-') => {
+                    self.position += 1;
+                    self.grow(1)?;
+                    expression -= self.term()?;
+                }
+                _ => return Ok(expression),
+            }
+        }
+    }
+
+    /// `factor ('*' factor)*`
+    fn term<F: Field + Arithmetic>(&mut self) -> Result<SymbolicExpression<F>, ParseError> {
+        let mut term = self.factor()?;
+        while *self.peek() == Token::Symbol('*') {
+            self.position += 1;
+            self.grow(1)?;
+            term *= self.factor()?;
+        }
+        Ok(term)
+    }
+
+    /// `'-' factor | atom ('^' number)?`
+    fn factor<F: Field + Arithmetic>(&mut self) -> Result<SymbolicExpression<F>, ParseError> {
+        if *self.peek() == Token::Symbol('-') {
+            self.position += 1;
+            self.grow(1)?;
+            return self.nested(|parser| Ok(-parser.factor()?));
+        }
+        let size_before = self.size;
+        let atom = self.atom()?;
+        if *self.peek() != Token::Symbol('^') {
+            return Ok(atom);
+        }
+        self.position += 1;
+        let exponent = self.number()?;
+        if exponent == 0 {
+            self.position -= 1;
+            return Err(self.error("exponents must be positive"));
+        }
+        if exponent > MAX_EXPONENT {
+            self.position -= 1;
+            return Err(self.error(&format!("exponents must be at most {}", MAX_EXPONENT)));
+        }
+        // The atom is already counted once; each further copy adds its nodes and a product.
+        let atom_size = self.size - size_before;
+        self.position -= 1;
+        self.grow((atom_size + 1).saturating_mul(exponent as usize - 1))?;
+        self.position += 1;
+        let mut power = atom.clone();
+        for _ in 1..exponent {
+            power *= atom.clone();
+        }
+        Ok(power)
+    }
+
+    /// A constant, a column on some row, a public value, or a parenthesized expression.
+    fn atom<F: Field + Arithmetic>(&mut self) -> Result<SymbolicExpression<F>, ParseError> {
+        match self.peek().clone() {
+            Token::Number(value) => {
+                let value =
+                    u32::try_from(value).map_err(|_| self.error("constants must fit in a u32"))?;
+                self.grow(1)?;
+                self.position += 1;
+                Ok(SymbolicExpression::from_u32(value))
+            }
+            Token::Symbol('(') => {
+                self.position += 1;
+                let expression = self.nested(Self::expression)?;
+                self.expect(')')?;
+                Ok(expression)
+            }
+            Token::Ident(name) if name == "pub" => {
+                self.position += 1;
+                self.expect('[')?;
+                let index = self.number()?;
+                let count = usize::try_from(index)
+                    .ok()
+                    .and_then(|index| index.checked_add(1))
+                    .ok_or_else(|| self.error("public value index is too large"))?;
+                self.expect(']')?;
+                self.grow(1)?;
+                self.num_public_values = self.num_public_values.max(count);
+                let index = count - 1;
+                Ok(SymbolicVariable::new(Entry::Public, index).into())
+            }
+            Token::Ident(name) => {
+                let index = self
+                    .columns
+                    .iter()
+                    .position(|column| *column == name)
+                    .ok_or_else(|| self.error(&format!("unknown column `{}`", name)))?;
+                self.grow(1)?;
+                self.position += 1;
+                let mut offset = 0;
+                while *self.peek() == Token::Symbol('\'') {
+                    self.position += 1;
+                    offset += 1;
+                }
+                Ok(SymbolicVariable::new(Entry::Main { offset }, index).into())
+            }
+            token => Err(self.error(&format!("unexpected {}", describe(&token)))),
+        }
+    }
+}
+
+enum Selector {
+    FirstRow,
+    LastRow,
+    Transition,
+}
+
+fn describe(token: &Token) -> String {
+    match token {
+        Token::Ident(name) => format!("`{}`", name),
+        Token::Number(value) => format!("`{}`", value),
+        Token::Symbol(c) => format!("`{}`", c),
+        Token::End => "end of input".to_string(),
+    }
+}
+
+/// The largest row offset of a main column in `expression`.
+fn max_offset<F: Field + Arithmetic>(expression: &SymbolicExpression<F>) -> usize {
+    match expression {
+        SymbolicExpression::Variable(SymbolicVariable {
+            entry: Entry::Main { offset },
+            ..
+        }) => *offset,
+        SymbolicExpression::Add { x, y, .. }
+        | SymbolicExpression::Sub { x, y, .. }
+        | SymbolicExpression::Mul { x, y, .. } => max_offset(x).max(max_offset(y)),
+        SymbolicExpression::Neg { x, .. } => max_offset(x),
+        _ => 0,
+    }
+}
@@ -0,0 +1,261 @@
+//! A lookup table of all values below `2^k`, for range-checking limbs through LogUp.
+//!
+//! `RangeCheckAir` has a preprocessed column holding `0..2^k` and a main column holding how many
+//! times each value is looked up, and receives every value from its bus with that multiplicity.
+//! Other AIRs send the values they want range-checked to the same bus, using the interactions
+//! built by `RangeCheckAir::range_checks` and constrained by `RangeCheckAir::eval_range_checks`
+//! alongside any others they have, and `RangeCheckMultiplicities` counts those sends to generate
+//! the table's main trace.
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+use icicle_core::field::Field;
+use icicle_core::traits::Arithmetic;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use tracing::instrument;
+
+use crate::air::{Air, BaseAir, BaseAirWithPublicValues, PairBuilder, PermutationAirBuilder};
+use crate::extension::ExtensionOf;
+use crate::field_requirement::FieldRequirement;
+use crate::interaction::{
+    eval_logup, generate_logup_trace, logup_permutation_width, row, Interaction, InteractionAir,
+    InteractionKind, LOGUP_NUM_CHALLENGES,
+};
+use crate::multi_phase::MultiPhaseAir;
+use crate::utils::field_to_u64;
+use crate::virtual_column::VirtualPairCol;
+
+/// A table of every value in `0..2^bits`, receiving range-check lookups on `bus`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct RangeCheckAir {
+    bits: usize,
+    bus: usize,
+}
+
+impl RangeCheckAir {
+    pub const fn new(bits: usize, bus: usize) -> Self {
+        assert!(bits <= 31, "range-check tables are limited to 31 bits");
+        Self { bits, bus }
+    }
+
+    pub const fn bits(&self) -> usize {
+        self.bits
+    }
+
+    pub const fn bus(&self) -> usize {
+        self.bus
+    }
+
+    /// The interactions which show that `value` is below `2^bits`, to be included in the
+    /// `InteractionAir::interactions` of the AIR doing the lookup.
+    ///
+    /// When `bits` is smaller than the table, `value * 2^(k - bits)` is looked up as well. That is
+    /// only sound if it cannot wrap around, so the field must meet `range_check_requirement(bits)`;
+    /// `eval_range_checks` requires it from the builder.
+    pub fn range_check<F: Field + Arithmetic>(
+        &self,
+        value: VirtualPairCol<F>,
+        bits: usize,
+    ) -> Vec<Interaction<F>> {
+        assert!(
+            bits <= self.bits,
+            "cannot range-check {} bits against a {}-bit table",
+            bits,
+            self.bits
+        );
+        let one = VirtualPairCol::constant(F::one());
+        if bits == self.bits {
+            return vec![Interaction::send(self.bus, vec![value], one)];
+        }
+
+        let shifted = value.scale(F::from_u32(1 << (self.bits - bits)));
+        vec![
+            Interaction::send(self.bus, vec![value], one.clone()),
+            Interaction::send(self.bus, vec![shifted], one),
+        ]
+    }
+
+    /// The field requirement of range-checking `bits` bits against this table, if there is one:
+    /// the field must be larger than `2^(2k - bits)` for the shifted lookup not to wrap around.
+    pub fn range_check_requirement(&self, bits: usize) -> Option<FieldRequirement> {
+        let shift = self.bits.checked_sub(bits).filter(|&shift| shift > 0)?;
+        Some(FieldRequirement::greater_than(
+            "RangeCheckAir::range_check",
+            1 << (self.bits + shift),
+        ))
+    }
+
+    /// The interactions of `range_check` for every `(value, bits)` of `checks`, in order.
+    pub fn range_checks<F: Field + Arithmetic>(
+        &self,
+        checks: &[(VirtualPairCol<F>, usize)],
+    ) -> Vec<Interaction<F>> {
+        checks
+            .iter()
+            .flat_map(|(value, bits)| self.range_check(value.clone(), *bits))
+            .collect()
+    }
+
+    /// Constrains the LogUp columns of an AIR which range-checks every `(value, bits)` of `checks`
+    /// against this table, and returns the running sum, as `eval_logup` does. `interactions` are
+    /// all of the AIR's interactions, in the order of its `InteractionAir::interactions`, and must
+    /// include `range_checks(checks)`; the others may be on any bus. Also requires the field size
+    /// each check needs from the builder.
+    pub fn eval_range_checks<AB>(
+        &self,
+        builder: &mut AB,
+        interactions: &[Interaction<AB::F>],
+        checks: &[(VirtualPairCol<AB::F>, usize)],
+    ) -> AB::ExprEF
+    where
+        AB: PermutationAirBuilder + PairBuilder,
+        AB::F: Arithmetic,
+    {
+        for (_, bits) in checks {
+            if let Some(requirement) = self.range_check_requirement(*bits) {
+                builder.require_field(requirement);
+            }
+        }
+        eval_logup(builder, interactions, None)
+    }
+
+    /// Starts counting the lookups made into this table.
+    pub fn multiplicities(&self) -> RangeCheckMultiplicities {
+        RangeCheckMultiplicities {
+            bus: self.bus,
+            counts: vec![0; 1 << self.bits],
+        }
+    }
+}
+
+impl<F: Field + Arithmetic> BaseAir<F> for RangeCheckAir {
+    fn width(&self) -> usize {
+        1
+    }
+
+    fn preprocessed_trace(&self) -> Option<RowMajorMatrix<F>> {
+        Some(RowMajorMatrix::new(
+            (0..1u32 << self.bits).map(F::from_u32).collect(),
+            1,
+        ))
+    }
+
+    fn preprocessed_width(&self) -> usize {
+        1
+    }
+
+    fn permutation_width(&self) -> usize {
+        logup_permutation_width(1)
+    }
+
+    fn num_challenges(&self) -> usize {
+        LOGUP_NUM_CHALLENGES
+    }
+
+    fn field_requirements(&self) -> Vec<FieldRequirement> {
+        vec![FieldRequirement::greater_than(
+            "RangeCheckAir",
+            (1 << self.bits) - 1,
+        )]
+    }
+}
+
+impl<F: Field + Arithmetic> BaseAirWithPublicValues<F> for RangeCheckAir {}
+
+impl<F: Field + Arithmetic> InteractionAir<F> for RangeCheckAir {
+    fn interactions(&self) -> Vec<Interaction<F>> {
+        vec![Interaction::receive(
+            self.bus,
+            vec![VirtualPairCol::single_preprocessed(0)],
+            VirtualPairCol::single_main(0),
+        )]
+    }
+}
+
+impl<F: Field + Arithmetic, EF: ExtensionOf<F>> MultiPhaseAir<F, EF> for RangeCheckAir {
+    fn generate_permutation_trace(
+        &self,
+        main: &RowMajorMatrix<F>,
+        challenges: &[EF],
+    ) -> RowMajorMatrix<EF> {
+        let preprocessed = BaseAir::<F>::preprocessed_trace(self);
+        generate_logup_trace(
+            &InteractionAir::<F>::interactions(self),
+            preprocessed.as_ref(),
+            main,
+            challenges,
+        )
+    }
+}
+
+impl<AB> Air<AB> for RangeCheckAir
+where
+    AB: PermutationAirBuilder + PairBuilder,
+    AB::F: Arithmetic,
+{
+    fn eval(&self, builder: &mut AB) {
+        let interactions = InteractionAir::<AB::F>::interactions(self);
+        eval_logup(builder, &interactions, None);
+    }
+}
+
+/// Counts the lookups sent to a `RangeCheckAir`, to generate its multiplicity column.
+#[derive(Clone, Debug)]
+pub struct RangeCheckMultiplicities {
+    bus: usize,
+    counts: Vec<u32>,
+}
+
+impl RangeCheckMultiplicities {
+    /// Records `multiplicity` lookups of `value`.
+    pub fn add<F: Field + Arithmetic>(&mut self, value: F, multiplicity: F) {
+        let index = field_to_u64(value)
+            .and_then(|value| usize::try_from(value).ok())
+            .filter(|&value| value < self.counts.len())
+            .unwrap_or_else(|| panic!("{} is out of range of the range-check table", value));
+        let multiplicity = field_to_u64(multiplicity)
+            .and_then(|multiplicity| u32::try_from(multiplicity).ok())
+            .expect("range-check multiplicity does not fit in a u32");
+        self.counts[index] = self.counts[index]
+            .checked_add(multiplicity)
+            .expect("range-check multiplicity overflowed a u32");
+    }
+
+    /// Records every lookup that `interactions` send to this table's bus over a whole trace.
+    #[instrument(name = "count range-check lookups", skip_all)]
+    pub fn add_trace<F: Field + Arithmetic>(
+        &mut self,
+        interactions: &[Interaction<F>],
+        preprocessed: Option<&RowMajorMatrix<F>>,
+        main: &RowMajorMatrix<F>,
+    ) {
+        let lookups: Vec<_> = interactions
+            .iter()
+            .filter(|interaction| {
+                interaction.bus == self.bus && interaction.kind == InteractionKind::Send
+            })
+            .collect();
+        for r in 0..main.height() {
+            let main_row = row(main, r);
+            let preprocessed_row = preprocessed.map_or(vec![], |preprocessed| row(preprocessed, r));
+            for interaction in &lookups {
+                let multiplicity = interaction
+                    .multiplicity
+                    .apply::<F, F>(&preprocessed_row, &main_row);
+                if multiplicity == F::zero() {
+                    continue;
+                }
+                let fields = interaction.eval_fields(&preprocessed_row, &main_row);
+                assert_eq!(fields.len(), 1, "range-check lookups have a single field");
+                self.add(fields[0], multiplicity);
+            }
+        }
+    }
+
+    /// The main trace of the `RangeCheckAir`.
+    pub fn into_trace<F: Field + Arithmetic>(self) -> RowMajorMatrix<F> {
+        RowMajorMatrix::new(self.counts.into_iter().map(F::from_u32).collect(), 1)
+    }
+}
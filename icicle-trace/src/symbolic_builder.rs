@@ -2,13 +2,22 @@
 // Original authors: Plonky3 authors, 2022
 // Modifications by Ingonyama, 2025
 
+use alloc::string::{String, ToString};
 use alloc::vec;
 use alloc::vec::Vec;
+use core::fmt::{self, Display, Formatter};
+use core::panic::Location;
 use icicle_core::traits::Arithmetic;
 use icicle_core::field::Field;
 use icicle_core::bignum::BigNum;
 
-use crate::{Air, AirBuilder, AirBuilderWithPublicValues, PairBuilder};
+use crate::config::AirConfig;
+use crate::extension::ExtensionOf;
+use crate::field_requirement::{check_field_requirements, FieldRequirement, FieldRequirementError};
+use crate::{
+    Air, AirBuilder, AirBuilderWithPublicValues, BaseAirWithPublicValues, ExtensionBuilder,
+    PairBuilder, PeriodicAirBuilder, PermutationAirBuilder,
+};
 use p3_matrix::dense::RowMajorMatrix;
 use tracing::instrument;
 
@@ -22,18 +31,13 @@ pub const fn log2_ceil_usize(n: usize) -> usize {
 }
 //
 #[instrument(name = "infer log of constraint degree", skip_all)]
-pub fn get_log_quotient_degree<F, A>(
-    air: &A,
-    preprocessed_width: usize,
-    num_public_values: usize,
-) -> usize
+pub fn get_log_quotient_degree<F, A>(air: &A) -> usize
 where
     F: Field + Arithmetic,
-    A: Air<SymbolicAirBuilder<F>>,
+    A: BaseAirWithPublicValues<F> + Air<SymbolicAirBuilder<F>>,
 {
     // We pad to at least degree 2, since a quotient argument doesn't make sense with smaller degrees.
-    let constraint_degree =
-        get_max_constraint_degree(air, preprocessed_width, num_public_values).max(2);
+    let constraint_degree = get_max_constraint_degree(air).max(2);
 
     // The quotient's actual degree is approximately (max_constraint_degree - 1) n,
     // where subtracting 1 comes from division by the zerofier.
@@ -42,77 +46,302 @@ where
 }
 
 #[instrument(name = "infer constraint degree", skip_all, level = "debug")]
-pub fn get_max_constraint_degree<F, A>(
-    air: &A,
-    preprocessed_width: usize,
-    num_public_values: usize,
-) -> usize
+pub fn get_max_constraint_degree<F, A>(air: &A) -> usize
 where
     F: Field + Arithmetic,
-    A: Air<SymbolicAirBuilder<F>>,
+    A: BaseAirWithPublicValues<F> + Air<SymbolicAirBuilder<F>>,
 {
-    get_symbolic_constraints(air, preprocessed_width, num_public_values)
+    let mut builder = SymbolicAirBuilder::new(&AirConfig::from_air(air));
+    air.eval(&mut builder);
+    // Extension constraints count too: the quotient has to accommodate the largest of either kind.
+    let base_degree = builder
+        .constraints
+        .iter()
+        .map(|c| c.expr.degree_multiple())
+        .max();
+    let extension_degree = builder
+        .extension_constraints
         .iter()
-        .map(|c| c.degree_multiple())
-        .max()
-        .unwrap_or(0)
+        .map(|c| c.expr.degree_multiple())
+        .max();
+    base_degree.max(extension_degree).unwrap_or(0)
 }
 
 #[instrument(name = "evaluate constraints symbolically", skip_all, level = "debug")]
-pub fn get_symbolic_constraints<F, A>(
-    air: &A,
-    preprocessed_width: usize,
-    num_public_values: usize,
-) -> Vec<SymbolicExpression<F>>
+pub fn get_symbolic_constraints<F, A>(air: &A) -> Vec<SymbolicExpression<F>>
 where
     F: Field + Arithmetic,
-    A: Air<SymbolicAirBuilder<F>>,
+    A: BaseAirWithPublicValues<F> + Air<SymbolicAirBuilder<F>>,
 {
-    let mut builder = SymbolicAirBuilder::new(preprocessed_width, air.width(), num_public_values);
+    let mut builder = SymbolicAirBuilder::new(&AirConfig::from_air(air));
     air.eval(&mut builder);
     builder.constraints()
 }
 
+/// Like `get_symbolic_constraints`, but fails if any constraint has degree above `max_degree`.
+#[instrument(name = "evaluate constraints with degree bound", skip_all, level = "debug")]
+pub fn get_symbolic_constraints_with_max_degree<F, A>(
+    air: &A,
+    max_degree: usize,
+) -> Result<Vec<SymbolicExpression<F>>, DegreeBoundError>
+where
+    F: Field + Arithmetic,
+    A: BaseAirWithPublicValues<F> + Air<SymbolicAirBuilder<F>>,
+{
+    let mut builder =
+        SymbolicAirBuilder::new(&AirConfig::from_air(air)).with_max_constraint_degree(max_degree);
+    air.eval(&mut builder);
+    builder.try_constraints()
+}
+
+/// Checks the field requirements declared by `air` and by the gadgets its constraints use against
+/// the characteristic of `F`.
+#[instrument(name = "check field requirements", skip_all, level = "debug")]
+pub fn check_air_field_requirements<F, A>(air: &A) -> Result<(), FieldRequirementError>
+where
+    F: Field + Arithmetic,
+    A: BaseAirWithPublicValues<F> + Air<SymbolicAirBuilder<F>>,
+{
+    check_field_requirements::<F>(&air.field_requirements())?;
+    let mut builder = SymbolicAirBuilder::new(&AirConfig::from_air(air));
+    air.eval(&mut builder);
+    builder.check_field_requirements()
+}
+
+/// Like `get_symbolic_constraints`, but keeps the scope and source location of each constraint.
+#[instrument(name = "evaluate named constraints symbolically", skip_all, level = "debug")]
+pub fn get_named_symbolic_constraints<F, A>(air: &A) -> Vec<NamedConstraint<F>>
+where
+    F: Field + Arithmetic,
+    A: BaseAirWithPublicValues<F> + Air<SymbolicAirBuilder<F>>,
+{
+    let mut builder = SymbolicAirBuilder::new(&AirConfig::from_air(air));
+    air.eval(&mut builder);
+    builder.named_constraints()
+}
+
+/// Like `get_symbolic_constraints`, but returns the constraints asserted over the extension `EF`,
+/// which may also refer to the AIR's permutation trace and verifier challenges.
+#[instrument(name = "evaluate extension constraints symbolically", skip_all, level = "debug")]
+pub fn get_symbolic_extension_constraints<F, EF, A>(air: &A) -> Vec<SymbolicExpression<EF>>
+where
+    F: Field + Arithmetic,
+    EF: ExtensionOf<F>,
+    A: BaseAirWithPublicValues<F> + Air<SymbolicAirBuilder<F, EF>>,
+{
+    let mut builder = SymbolicAirBuilder::new(&AirConfig::from_air(air));
+    air.eval(&mut builder);
+    builder.extension_constraints()
+}
+
+/// A symbolic constraint together with where it was asserted.
+#[derive(Clone, Debug)]
+pub struct NamedConstraint<F: Field + Arithmetic> {
+    /// The enclosing scopes, outermost first, joined with `/`. Empty outside of any scope.
+    pub name: String,
+    pub location: &'static Location<'static>,
+    pub expr: SymbolicExpression<F>,
+}
+
+/// A constraint whose degree exceeds the bound a `SymbolicAirBuilder` was configured with.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DegreeViolation {
+    /// Whether this is an extension constraint, asserted through `assert_zero_ext`.
+    pub extension: bool,
+    /// The position of the constraint in evaluation order, among the base or extension
+    /// constraints.
+    pub index: usize,
+    pub degree: usize,
+    pub name: String,
+    pub location: &'static Location<'static>,
+}
+
+/// Returned when an AIR has constraints above the configured maximum degree.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DegreeBoundError {
+    pub max_degree: usize,
+    pub violations: Vec<DegreeViolation>,
+}
+
+impl Display for DegreeBoundError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} constraint(s) exceed the maximum degree {}",
+            self.violations.len(),
+            self.max_degree
+        )?;
+        for v in &self.violations {
+            let kind = if v.extension {
+                "extension constraint"
+            } else {
+                "constraint"
+            };
+            write!(f, "\n  {} {} has degree {}", kind, v.index, v.degree)?;
+            if !v.name.is_empty() {
+                write!(f, " in `{}`", v.name)?;
+            }
+            write!(f, " at {}", v.location)?;
+        }
+        Ok(())
+    }
+}
+
+impl core::error::Error for DegreeBoundError {}
+
+fn degree_violations<T: Field + Arithmetic>(
+    constraints: &[NamedConstraint<T>],
+    extension: bool,
+    max_degree: usize,
+) -> impl Iterator<Item = DegreeViolation> + '_ {
+    constraints
+        .iter()
+        .enumerate()
+        .filter(move |(_, c)| c.expr.degree_multiple() > max_degree)
+        .map(move |(index, c)| DegreeViolation {
+            extension,
+            index,
+            degree: c.expr.degree_multiple(),
+            name: c.name.clone(),
+            location: c.location,
+        })
+}
+
 #[derive(Debug)]
-pub struct SymbolicAirBuilder<F: Field + Arithmetic> {
+pub struct SymbolicAirBuilder<F: Field + Arithmetic, EF: ExtensionOf<F> = F> {
     preprocessed: RowMajorMatrix<SymbolicVariable<F>>,
     main: RowMajorMatrix<SymbolicVariable<F>>,
     public_values: Vec<SymbolicVariable<F>>,
-    constraints: Vec<SymbolicExpression<F>>,
+    periodic_values: Vec<SymbolicVariable<F>>,
+    permutation: RowMajorMatrix<SymbolicVariable<EF>>,
+    permutation_challenges: Vec<SymbolicVariable<EF>>,
+    window_size: usize,
+    max_constraint_degree: Option<usize>,
+    field_requirements: Vec<FieldRequirement>,
+    scopes: Vec<String>,
+    constraints: Vec<NamedConstraint<F>>,
+    extension_constraints: Vec<NamedConstraint<EF>>,
 }
 
-impl<F: Field + Arithmetic> SymbolicAirBuilder<F> {
-    pub(crate) fn new(preprocessed_width: usize, width: usize, num_public_values: usize) -> Self {
-        let prep_values = [0, 1]
-            .into_iter()
+impl<F: Field + Arithmetic, EF: ExtensionOf<F>> SymbolicAirBuilder<F, EF> {
+    /// Creates a builder with one variable per cell of the `config.window_size` rows of each
+    /// trace, and one per public value, periodic column and challenge.
+    pub fn new(config: &AirConfig) -> Self {
+        let AirConfig {
+            main_width: width,
+            preprocessed_width,
+            permutation_width,
+            num_challenges,
+            num_public_values,
+            num_periodic_columns,
+            window_size,
+        } = *config;
+        assert!(window_size >= 1, "window size must be at least 1");
+        let prep_values = (0..window_size)
             .flat_map(|offset| {
                 (0..preprocessed_width)
                     .map(move |index| SymbolicVariable::new(Entry::Preprocessed { offset }, index))
             })
             .collect();
-        let main_values = [0, 1]
-            .into_iter()
+        let main_values = (0..window_size)
             .flat_map(|offset| {
                 (0..width).map(move |index| SymbolicVariable::new(Entry::Main { offset }, index))
             })
             .collect();
+        let perm_values = (0..window_size)
+            .flat_map(|offset| {
+                (0..permutation_width)
+                    .map(move |index| SymbolicVariable::new(Entry::Permutation { offset }, index))
+            })
+            .collect();
         let public_values = (0..num_public_values)
             .map(move |index| SymbolicVariable::new(Entry::Public, index))
             .collect();
+        let periodic_values = (0..num_periodic_columns)
+            .map(move |index| SymbolicVariable::new(Entry::Periodic, index))
+            .collect();
+        let permutation_challenges = (0..num_challenges)
+            .map(move |index| SymbolicVariable::new(Entry::Challenge, index))
+            .collect();
         Self {
             preprocessed: RowMajorMatrix::new(prep_values, preprocessed_width),
             main: RowMajorMatrix::new(main_values, width),
             public_values,
+            periodic_values,
+            permutation: RowMajorMatrix::new(perm_values, permutation_width),
+            permutation_challenges,
+            window_size,
+            max_constraint_degree: None,
+            field_requirements: vec![],
+            scopes: vec![],
             constraints: vec![],
+            extension_constraints: vec![],
+        }
+    }
+
+    /// Bounds the degree of every constraint asserted to this builder; see `try_constraints`.
+    pub fn with_max_constraint_degree(mut self, max_degree: usize) -> Self {
+        self.max_constraint_degree = Some(max_degree);
+        self
+    }
+
+    /// Returns the constraints, or every base or extension constraint above the configured maximum
+    /// degree.
+    pub fn try_constraints(self) -> Result<Vec<SymbolicExpression<F>>, DegreeBoundError> {
+        if let Some(max_degree) = self.max_constraint_degree {
+            let violations: Vec<_> = degree_violations(&self.constraints, false, max_degree)
+                .chain(degree_violations(
+                    &self.extension_constraints,
+                    true,
+                    max_degree,
+                ))
+                .collect();
+            if !violations.is_empty() {
+                return Err(DegreeBoundError {
+                    max_degree,
+                    violations,
+                });
+            }
         }
+        Ok(self.constraints())
     }
 
-    pub(crate) fn constraints(self) -> Vec<SymbolicExpression<F>> {
+    /// The field requirements declared through `require_field` so far.
+    pub fn field_requirements(&self) -> &[FieldRequirement] {
+        &self.field_requirements
+    }
+
+    /// Checks the declared field requirements against the characteristic of `F`.
+    pub fn check_field_requirements(&self) -> Result<(), FieldRequirementError> {
+        check_field_requirements::<F>(&self.field_requirements)
+    }
+
+    pub fn named_constraints(self) -> Vec<NamedConstraint<F>> {
         self.constraints
     }
+
+    pub fn constraints(self) -> Vec<SymbolicExpression<F>> {
+        self.constraints.into_iter().map(|c| c.expr).collect()
+    }
+
+    pub fn extension_constraints(self) -> Vec<SymbolicExpression<EF>> {
+        self.extension_constraints
+            .into_iter()
+            .map(|c| c.expr)
+            .collect()
+    }
+
+    #[track_caller]
+    fn named<T: Field + Arithmetic>(&self, expr: SymbolicExpression<T>) -> NamedConstraint<T> {
+        NamedConstraint {
+            name: self.scopes.join("/"),
+            location: Location::caller(),
+            expr,
+        }
+    }
 }
 
-impl<F: Field + Arithmetic> AirBuilder for SymbolicAirBuilder<F> {
+impl<F: Field + Arithmetic, EF: ExtensionOf<F>> AirBuilder for SymbolicAirBuilder<F, EF> {
     type F = F;
     type Expr = SymbolicExpression<F>;
     type Var = SymbolicVariable<F>;
@@ -131,10 +360,34 @@ impl<F: Field + Arithmetic> AirBuilder for SymbolicAirBuilder<F> {
     }
 
     fn is_transition_window(&self, size: usize) -> Self::Expr {
+        assert!(
+            (2..=self.window_size).contains(&size),
+            "transition window of size {} does not fit in a window of {} rows",
+            size,
+            self.window_size
+        );
         if size == 2 {
             SymbolicExpression::IsTransition
         } else {
-            panic!("uni-stark only supports a window size of 2")
+            SymbolicExpression::IsTransitionWindow(size)
+        }
+    }
+
+    fn is_row(&self, row: usize) -> Self::Expr {
+        SymbolicExpression::IsRow(row)
+    }
+
+    fn is_row_mod(&self, residue: usize, period: usize) -> Self::Expr {
+        assert!(
+            period.is_power_of_two() && residue < period,
+            "invalid periodic selector {} mod {}",
+            residue,
+            period
+        );
+        if period == 1 {
+            SymbolicExpression::one()
+        } else {
+            SymbolicExpression::IsPeriodicRow { residue, period }
         }
     }
 
@@ -148,20 +401,73 @@ impl<F: Field + Arithmetic> AirBuilder for SymbolicAirBuilder<F> {
         SymbolicExpression::from_u32(val)
     }
 
+    fn require_field(&mut self, requirement: FieldRequirement) {
+        if !self.field_requirements.contains(&requirement) {
+            self.field_requirements.push(requirement);
+        }
+    }
+
+    fn push_scope(&mut self, name: &str) {
+        self.scopes.push(name.to_string());
+    }
+
+    fn pop_scope(&mut self) {
+        self.scopes.pop();
+    }
+
     fn assert_zero<I: Into<Self::Expr>>(&mut self, x: I) {
-        self.constraints.push(x.into());
+        let constraint = self.named(x.into());
+        self.constraints.push(constraint);
     }
 }
 
-impl<F: Field + Arithmetic> AirBuilderWithPublicValues for SymbolicAirBuilder<F> {
+impl<F: Field + Arithmetic, EF: ExtensionOf<F>> AirBuilderWithPublicValues
+    for SymbolicAirBuilder<F, EF>
+{
     type PublicVar = SymbolicVariable<F>;
     fn public_values(&self) -> &[Self::PublicVar] {
         &self.public_values
     }
 }
 
-impl<F: Field + Arithmetic> PairBuilder for SymbolicAirBuilder<F> {
+impl<F: Field + Arithmetic, EF: ExtensionOf<F>> PairBuilder for SymbolicAirBuilder<F, EF> {
     fn preprocessed(&self) -> Self::M {
         self.preprocessed.clone()
     }
 }
+
+impl<F: Field + Arithmetic, EF: ExtensionOf<F>> PeriodicAirBuilder for SymbolicAirBuilder<F, EF> {
+    fn periodic_values(&self) -> &[Self::Var] {
+        &self.periodic_values
+    }
+}
+
+impl<F: Field + Arithmetic, EF: ExtensionOf<F>> ExtensionBuilder for SymbolicAirBuilder<F, EF> {
+    type EF = EF;
+    type ExprEF = SymbolicExpression<EF>;
+    type VarEF = SymbolicVariable<EF>;
+
+    fn lift<I: Into<Self::Expr>>(&self, x: I) -> Self::ExprEF {
+        x.into().lift()
+    }
+
+    fn assert_zero_ext<I: Into<Self::ExprEF>>(&mut self, x: I) {
+        let constraint = self.named(x.into());
+        self.extension_constraints.push(constraint);
+    }
+}
+
+impl<F: Field + Arithmetic, EF: ExtensionOf<F>> PermutationAirBuilder
+    for SymbolicAirBuilder<F, EF>
+{
+    type MP = RowMajorMatrix<Self::VarEF>;
+    type RandomVar = SymbolicVariable<EF>;
+
+    fn permutation(&self) -> Self::MP {
+        self.permutation.clone()
+    }
+
+    fn permutation_randomness(&self) -> &[Self::RandomVar] {
+        &self.permutation_challenges
+    }
+}
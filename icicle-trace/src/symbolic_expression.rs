@@ -15,6 +15,7 @@ use icicle_core::traits::Arithmetic;
 use icicle_core::field::Field;
 use icicle_core::bignum::BigNum;
 
+use crate::extension::ExtensionOf;
 use crate::symbolic_variable::SymbolicVariable;
 
 /// An expression over `SymbolicVariable`s.
@@ -24,6 +25,12 @@ pub enum SymbolicExpression<F: Field + Arithmetic> {
     IsFirstRow,
     IsLastRow,
     IsTransition,
+    /// Nonzero on every row except the last `size - 1`, i.e. wherever a window of `size` rows fits.
+    IsTransitionWindow(usize),
+    /// Nonzero only on the given row.
+    IsRow(usize),
+    /// Nonzero only on rows `i` with `i % period == residue`.
+    IsPeriodicRow { residue: usize, period: usize },
     Constant(F),
     Add {
         x: Arc<Self>,
@@ -54,6 +61,11 @@ impl<F: Field + Arithmetic> SymbolicExpression<F> {
             SymbolicExpression::IsFirstRow => 1,
             SymbolicExpression::IsLastRow => 1,
             SymbolicExpression::IsTransition => 0,
+            // A product of `size - 1` linear factors, so its degree does not grow with `n`.
+            SymbolicExpression::IsTransitionWindow(_) => 0,
+            // Lagrange selectors, of degree below `n` like `IsFirstRow` and `IsLastRow`.
+            SymbolicExpression::IsRow(_) => 1,
+            SymbolicExpression::IsPeriodicRow { .. } => 1,
             SymbolicExpression::Constant(_) => 0,
             SymbolicExpression::Add {
                 degree_multiple, ..
@@ -81,6 +93,54 @@ impl<F: Field + Arithmetic> SymbolicExpression<F> {
     pub fn from_u32(val: u32) -> Self {
         Self::Constant(F::from_u32(val))
     }
+
+    /// Maps this expression into the extension field `EF`, keeping its variables and degrees.
+    pub fn lift<EF: ExtensionOf<F>>(&self) -> SymbolicExpression<EF> {
+        match self {
+            Self::Variable(v) => SymbolicExpression::Variable(SymbolicVariable::new(v.entry, v.index)),
+            Self::IsFirstRow => SymbolicExpression::IsFirstRow,
+            Self::IsLastRow => SymbolicExpression::IsLastRow,
+            Self::IsTransition => SymbolicExpression::IsTransition,
+            Self::IsTransitionWindow(size) => SymbolicExpression::IsTransitionWindow(*size),
+            Self::IsRow(row) => SymbolicExpression::IsRow(*row),
+            Self::IsPeriodicRow { residue, period } => SymbolicExpression::IsPeriodicRow {
+                residue: *residue,
+                period: *period,
+            },
+            Self::Constant(c) => SymbolicExpression::Constant(EF::from_base(*c)),
+            Self::Add {
+                x,
+                y,
+                degree_multiple,
+            } => SymbolicExpression::Add {
+                x: Arc::new(x.lift()),
+                y: Arc::new(y.lift()),
+                degree_multiple: *degree_multiple,
+            },
+            Self::Sub {
+                x,
+                y,
+                degree_multiple,
+            } => SymbolicExpression::Sub {
+                x: Arc::new(x.lift()),
+                y: Arc::new(y.lift()),
+                degree_multiple: *degree_multiple,
+            },
+            Self::Neg { x, degree_multiple } => SymbolicExpression::Neg {
+                x: Arc::new(x.lift()),
+                degree_multiple: *degree_multiple,
+            },
+            Self::Mul {
+                x,
+                y,
+                degree_multiple,
+            } => SymbolicExpression::Mul {
+                x: Arc::new(x.lift()),
+                y: Arc::new(y.lift()),
+                degree_multiple: *degree_multiple,
+            },
+        }
+    }
 }
 
 impl<F: Field + Arithmetic + Display> Display for SymbolicExpression<F> {
@@ -90,6 +150,11 @@ impl<F: Field + Arithmetic + Display> Display for SymbolicExpression<F> {
             Self::IsFirstRow => write!(f, "IsFirstRow"),
             Self::IsLastRow => write!(f, "IsLastRow"),
             Self::IsTransition => write!(f, "IsTransition"),
+            Self::IsTransitionWindow(size) => write!(f, "IsTransitionWindow({})", size),
+            Self::IsRow(row) => write!(f, "IsRow({})", row),
+            Self::IsPeriodicRow { residue, period } => {
+                write!(f, "IsPeriodicRow({} mod {})", residue, period)
+            }
             Self::Constant(val) => write!(f, "{}", val),
             Self::Add { x, y, .. } => write!(f, "({} + {})", &**x, &**y),
             Self::Sub { x, y, .. } => write!(f, "({} - {})", &**x, &**y),
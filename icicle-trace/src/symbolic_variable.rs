@@ -18,6 +18,8 @@ pub enum Entry {
     Permutation { offset: usize },
     Public,
     Challenge,
+    /// A periodic column, evaluated at the current row.
+    Periodic,
 }
 
 /// A variable within the evaluation window, i.e. a column in either the local or next row.
@@ -40,6 +42,8 @@ impl<F: Field + Arithmetic> SymbolicVariable<F> {
     pub const fn degree_multiple(&self) -> usize {
         match self.entry {
             Entry::Preprocessed { .. } | Entry::Main { .. } | Entry::Permutation { .. } => 1,
+            // A column with period `p` interpolates to a polynomial of degree `(p - 1) n / p < n`.
+            Entry::Periodic => 1,
             Entry::Public | Entry::Challenge => 0,
         }
     }
@@ -0,0 +1,147 @@
+//! Typed views of a trace, with one column struct per row.
+//!
+//! `TraceView` and `TraceViewMut` borrow the values of a `RowMajorMatrix` as a slice of
+//! `AirColumns` structs, after checking that the matrix has the struct's width. Generators can
+//! then fill rows by field name, one row or one block of rows at a time, sequentially or with
+//! rayon.
+
+use core::ops::{Index, IndexMut};
+use core::slice;
+
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use rayon::prelude::*;
+
+use crate::columns::{borrow_rows, borrow_rows_mut, AirColumns};
+
+/// The rows of a trace, each viewed as the column struct `C`.
+#[derive(Debug)]
+pub struct TraceView<'a, C> {
+    rows: &'a [C],
+}
+
+impl<'a, C> TraceView<'a, C> {
+    /// Panics if `trace` is not exactly as wide as `C`.
+    pub fn new<T: Clone + Send + Sync>(trace: &'a RowMajorMatrix<T>) -> Self
+    where
+        C: AirColumns<T>,
+    {
+        assert_eq!(
+            trace.width(),
+            C::NUM_COLUMNS,
+            "trace width does not match the column struct"
+        );
+        Self {
+            rows: borrow_rows(&trace.values),
+        }
+    }
+
+    pub fn height(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn rows(&self) -> &'a [C] {
+        self.rows
+    }
+
+    pub fn iter(&self) -> slice::Iter<'a, C> {
+        self.rows.iter()
+    }
+
+    /// Blocks of `size` consecutive rows; the last block is shorter if `size` does not divide the
+    /// height.
+    pub fn chunks(&self, size: usize) -> slice::Chunks<'a, C> {
+        self.rows.chunks(size)
+    }
+}
+
+impl<'a, C: Sync> TraceView<'a, C> {
+    pub fn par_iter(&self) -> rayon::slice::Iter<'a, C> {
+        self.rows.par_iter()
+    }
+
+    pub fn par_chunks(&self, size: usize) -> rayon::slice::Chunks<'a, C> {
+        self.rows.par_chunks(size)
+    }
+}
+
+impl<C> Index<usize> for TraceView<'_, C> {
+    type Output = C;
+
+    fn index(&self, row: usize) -> &C {
+        &self.rows[row]
+    }
+}
+
+/// The rows of a trace, each viewed mutably as the column struct `C`.
+#[derive(Debug)]
+pub struct TraceViewMut<'a, C> {
+    rows: &'a mut [C],
+}
+
+impl<'a, C> TraceViewMut<'a, C> {
+    /// Panics if `trace` is not exactly as wide as `C`.
+    pub fn new<T: Clone + Send + Sync>(trace: &'a mut RowMajorMatrix<T>) -> Self
+    where
+        C: AirColumns<T>,
+    {
+        assert_eq!(
+            trace.width(),
+            C::NUM_COLUMNS,
+            "trace width does not match the column struct"
+        );
+        Self {
+            rows: borrow_rows_mut(&mut trace.values),
+        }
+    }
+
+    pub fn height(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn rows(&self) -> &[C] {
+        self.rows
+    }
+
+    pub fn rows_mut(&mut self) -> &mut [C] {
+        self.rows
+    }
+
+    pub fn iter(&self) -> slice::Iter<'_, C> {
+        self.rows.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> slice::IterMut<'_, C> {
+        self.rows.iter_mut()
+    }
+
+    /// Blocks of `size` consecutive rows, such as the rounds of one permutation; the last block is
+    /// shorter if `size` does not divide the height.
+    pub fn chunks_mut(&mut self, size: usize) -> slice::ChunksMut<'_, C> {
+        self.rows.chunks_mut(size)
+    }
+}
+
+impl<C: Send> TraceViewMut<'_, C> {
+    pub fn par_iter_mut(&mut self) -> rayon::slice::IterMut<'_, C> {
+        self.rows.par_iter_mut()
+    }
+
+    pub fn par_chunks_mut(&mut self, size: usize) -> rayon::slice::ChunksMut<'_, C> {
+        self.rows.par_chunks_mut(size)
+    }
+}
+
+impl<C> Index<usize> for TraceViewMut<'_, C> {
+    type Output = C;
+
+    fn index(&self, row: usize) -> &C {
+        &self.rows[row]
+    }
+}
+
+impl<C> IndexMut<usize> for TraceViewMut<'_, C> {
+    fn index_mut(&mut self, row: usize) -> &mut C {
+        &mut self.rows[row]
+    }
+}
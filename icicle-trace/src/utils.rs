@@ -10,8 +10,21 @@ use icicle_core::traits::Arithmetic;
 use icicle_core::field::Field;
 use icicle_core::bignum::BigNum;
 
+use crate::field_requirement::FieldRequirement;
 use crate::AirBuilder;
 
+/// The field size `add3` relies on.
+pub const ADD3_FIELD_REQUIREMENT: FieldRequirement =
+    FieldRequirement::greater_than("utils::add3", 3 << 16);
+
+/// The field size `add2` relies on.
+pub const ADD2_FIELD_REQUIREMENT: FieldRequirement =
+    FieldRequirement::greater_than("utils::add2", 1 << 17);
+
+/// The field size `xor_32_shift` relies on, so that packing 16 bits into a limb cannot overflow.
+pub const XOR_32_SHIFT_FIELD_REQUIREMENT: FieldRequirement =
+    FieldRequirement::greater_than("utils::xor_32_shift", (1 << 16) - 1);
+
 /// Convert a 32-bit integer into an array of 32 0 or 1 field elements.
 ///
 /// The output array is in little-endian order.
@@ -35,6 +48,7 @@ pub fn u32_to_bits_le<FA: Field + Arithmetic>(val: u32) -> [FA; 32] {
 ///
 /// This function assumes we are working over a field with characteristic `P > 3*2^16`.
 #[inline]
+#[track_caller]
 pub fn add3<AB: AirBuilder>(
     builder: &mut AB,
     a: &[AB::Var; 2],
@@ -42,6 +56,8 @@ pub fn add3<AB: AirBuilder>(
     c: &[AB::Expr; 2],
     d: &[AB::Expr; 2],
 ) {
+    builder.require_field(ADD3_FIELD_REQUIREMENT);
+
     // Define:
     //  acc    = a - b - c - d (mod P)
     //  acc_16 = a[0] - b[0] - c[0] - d[0] (mod P)
@@ -93,12 +109,15 @@ pub fn add3<AB: AirBuilder>(
 ///
 /// This function assumes we are working over a field with characteristic `P > 2^17`.
 #[inline]
+#[track_caller]
 pub fn add2<AB: AirBuilder>(
     builder: &mut AB,
     a: &[AB::Var; 2],
     b: &[AB::Var; 2],
     c: &[AB::Expr; 2],
 ) {
+    builder.require_field(ADD2_FIELD_REQUIREMENT);
+
     // Define:
     //  acc    = a - b - c (mod P)
     //  acc_16 = a[0] - b[0] - c[0] (mod P)
@@ -143,6 +162,7 @@ pub fn add2<AB: AirBuilder>(
 // range checked as part of this function.
 
 #[inline]
+#[track_caller]
 pub fn xor_32_shift<AB: AirBuilder>(
     builder: &mut AB,
     a: &[AB::Var; 2],
@@ -150,6 +170,8 @@ pub fn xor_32_shift<AB: AirBuilder>(
     c: &[AB::Var; 32],
     shift: usize,
 ) {
+    builder.require_field(XOR_32_SHIFT_FIELD_REQUIREMENT);
+
     // First we range check all elements of c.
     c.iter().for_each(|elem| builder.assert_bool(elem.clone()));
 
@@ -194,7 +216,7 @@ pub const fn indices_arr<const N: usize>() -> [usize; N] {
 /// The output array is in little-endian order.
 #[inline]
 pub fn u64_to_16_bit_limbs<R: Field + Arithmetic>(val: u64) -> [R; 4] {
-    array::from_fn(|i| R::from_u32((val >> (16 * i)) as u16 as u32))
+    u64_to_limbs::<R, 16, 4>(val)
 }
 
 /// Convert a 64-bit integer into an array of 64 0 or 1 field elements.
@@ -202,5 +224,129 @@ pub fn u64_to_16_bit_limbs<R: Field + Arithmetic>(val: u64) -> [R; 4] {
 /// The output array is in little-endian order.
 #[inline]
 pub fn u64_to_bits_le<R: Field + Arithmetic>(val: u64) -> [R; 64] {
-    array::from_fn(|i| R::from_u32((val & (1 << i) != 0) as u32))
+    u64_to_limbs::<R, 1, 64>(val)
+}
+
+/// Fails to compile unless `LIMB_BITS` is a limb width the limb helpers support.
+#[inline(always)]
+fn assert_limb_bits<const LIMB_BITS: usize>() {
+    const {
+        assert!(
+            1 <= LIMB_BITS && LIMB_BITS <= 32,
+            "limbs must be between 1 and 32 bits"
+        )
+    };
+}
+
+/// The field size needed for `LIMB_BITS`-bit limbs to be stored without reduction.
+///
+/// Like every limb helper, it only accepts limbs of 1 to 32 bits:
+///
+/// ```compile_fail
+/// icicle_trace::utils::limb_field_requirement::<64>();
+/// ```
+pub fn limb_field_requirement<const LIMB_BITS: usize>() -> FieldRequirement {
+    assert_limb_bits::<LIMB_BITS>();
+    FieldRequirement::greater_than("utils::limbs", (1 << LIMB_BITS) - 1)
+}
+
+/// Convert the low `N * LIMB_BITS` bits of a 64-bit integer into `N` limbs of `LIMB_BITS` bits.
+///
+/// The output array is in little-endian order. `LIMB_BITS` must be between 1 and 32, and the field
+/// must satisfy `limb_field_requirement::<LIMB_BITS>()`.
+#[inline]
+pub fn u64_to_limbs<R: Field + Arithmetic, const LIMB_BITS: usize, const N: usize>(
+    val: u64,
+) -> [R; N] {
+    assert_limb_bits::<LIMB_BITS>();
+    let mask = (1u64 << LIMB_BITS) - 1;
+    array::from_fn(|i| {
+        let limb = val.checked_shr((i * LIMB_BITS) as u32).unwrap_or(0) & mask;
+        R::from_u32(limb as u32)
+    })
+}
+
+/// Convert the low `N * LIMB_BITS` bits of a 32-bit integer into `N` limbs of `LIMB_BITS` bits.
+///
+/// The output array is in little-endian order. See `u64_to_limbs`.
+#[inline]
+pub fn u32_to_limbs<R: Field + Arithmetic, const LIMB_BITS: usize, const N: usize>(
+    val: u32,
+) -> [R; N] {
+    u64_to_limbs::<R, LIMB_BITS, N>(val as u64)
+}
+
+/// Recombine little-endian limbs of `LIMB_BITS` bits into a 64-bit integer.
+///
+/// Panics if a limb does not fit in `LIMB_BITS` bits or a nonzero limb lies past the 64th bit.
+pub fn limbs_to_u64<R: Field + Arithmetic, const LIMB_BITS: usize>(limbs: &[R]) -> u64 {
+    assert_limb_bits::<LIMB_BITS>();
+    limbs.iter().enumerate().fold(0, |acc, (i, limb)| {
+        let limb = field_to_u64(*limb)
+            .filter(|&limb| limb >> LIMB_BITS == 0)
+            .unwrap_or_else(|| panic!("{} does not fit in {} bits", limb, LIMB_BITS));
+        // Zero limbs past the 64th bit are allowed, e.g. when padding a shorter value.
+        if limb == 0 {
+            return acc;
+        }
+        let shifted = u32::try_from(i * LIMB_BITS)
+            .ok()
+            .and_then(|shift| limb.checked_shl(shift))
+            .filter(|shifted| shifted >> (i * LIMB_BITS) == limb)
+            .expect("limbs do not fit in 64 bits");
+        acc | shifted
+    })
+}
+
+/// Pack little-endian limbs of `LIMB_BITS` bits into a single expression.
+///
+/// The result only equals the integer value of the limbs if that value is smaller than `P`.
+pub fn pack_limbs<AB: AirBuilder, const LIMB_BITS: usize, I>(builder: &AB, limbs: I) -> AB::Expr
+where
+    I: DoubleEndedIterator,
+    I::Item: Into<AB::Expr>,
+{
+    assert_limb_bits::<LIMB_BITS>();
+    let base = if LIMB_BITS < 32 {
+        builder.from_u32(1 << LIMB_BITS)
+    } else {
+        builder.from_u32(1 << 16) * builder.from_u32(1 << 16)
+    };
+    limbs
+        .rev()
+        .fold(builder.zero(), |acc, limb| acc * base.clone() + limb.into())
+}
+
+/// Range check each limb to `LIMB_BITS` bits by decomposing it into the given bit columns.
+///
+/// The bits are asserted to be boolean and to pack to their limb. Fill them with
+/// `u64_to_limbs::<_, 1, LIMB_BITS>` of each limb.
+#[track_caller]
+pub fn range_check_limbs<AB: AirBuilder, const LIMB_BITS: usize>(
+    builder: &mut AB,
+    limbs: &[AB::Var],
+    bits: &[[AB::Var; LIMB_BITS]],
+) {
+    assert_eq!(limbs.len(), bits.len(), "each limb needs its own bits");
+    builder.require_field(limb_field_requirement::<LIMB_BITS>());
+    for (&limb, limb_bits) in limbs.iter().zip(bits) {
+        limb_bits.iter().for_each(|&bit| builder.assert_bool(bit));
+        let packed = builder.pack_bits_le(limb_bits.iter().copied());
+        builder.assert_eq(limb, packed);
+    }
+}
+
+/// Interpret a field element as an integer, if it fits in a `u64`.
+pub fn field_to_u64<R: BigNum>(value: R) -> Option<u64> {
+    let bytes = value.to_bytes_le();
+    if bytes.iter().skip(8).any(|&byte| byte != 0) {
+        return None;
+    }
+    Some(
+        bytes
+            .iter()
+            .take(8)
+            .rev()
+            .fold(0, |acc, &byte| (acc << 8) | byte as u64),
+    )
 }
@@ -0,0 +1,453 @@
+//! Stacking two AIRs in one trace, each on its own segment of rows.
+//!
+//! `VerticalAir` puts the rows of its `top` AIR before those of its `bottom` AIR, sharing columns:
+//! the narrower AIR's rows are padded with zeros to the wider one's width. Two selector columns are
+//! appended: `is_top`, which is one exactly on the top segment, and `is_bottom_first`, which marks
+//! the first row of the bottom segment. Each AIR is evaluated on a `SegmentAirBuilder` which gates
+//! its constraints by its segment and moves its first row, last row and transitions to the
+//! segment's edges. Nesting `VerticalAir`s stacks any number of AIRs.
+
+use alloc::vec::Vec;
+use core::fmt;
+use core::ops::Range;
+
+use icicle_core::field::Field;
+use icicle_core::traits::Arithmetic;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+
+use crate::air::{
+    Air, AirBuilder, AirBuilderWithPublicValues, BaseAir, BaseAirWithPublicValues,
+    ExtensionBuilder, PairBuilder, PeriodicAirBuilder, PermutationAirBuilder,
+};
+use crate::extension::ExtensionOf;
+use crate::field_requirement::FieldRequirement;
+use crate::horizontal::columns;
+use crate::interaction::row;
+use crate::multi_phase::MultiPhaseAir;
+
+/// Two AIRs on consecutive row segments of one trace, both of which must be nonempty.
+///
+/// Both AIRs see all public values, and draw their challenges from the same list. Each AIR's
+/// auxiliary trace occupies the first columns of the shared one on its segment. Preprocessed traces
+/// are not supported, as the segment heights are only known once the trace is generated. For the
+/// same reason `bottom` may not use row selectors, and its periodic columns, which repeat from the
+/// first row of the trace, only line up with its segment because the segment is constrained to
+/// start on a multiple of their lengths. Gating raises the degree of each AIR's constraints by one,
+/// and that of its boundary and transition constraints by two.
+#[derive(Clone, Debug)]
+pub struct VerticalAir<A, B> {
+    pub top: A,
+    pub bottom: B,
+}
+
+impl<A, B> VerticalAir<A, B> {
+    pub const fn new(top: A, bottom: B) -> Self {
+        Self { top, bottom }
+    }
+
+    /// The number of columns shared by the two AIRs; the selectors follow them.
+    pub fn shared_width<F>(&self) -> usize
+    where
+        A: BaseAir<F>,
+        B: BaseAir<F>,
+    {
+        self.top.width().max(self.bottom.width())
+    }
+
+    /// Stacks a trace of `top` on a trace of `bottom`, and fills in the selector columns.
+    pub fn stack_traces<F>(
+        &self,
+        top: &RowMajorMatrix<F>,
+        bottom: &RowMajorMatrix<F>,
+    ) -> RowMajorMatrix<F>
+    where
+        F: Field + Arithmetic,
+        A: BaseAir<F>,
+        B: BaseAir<F>,
+    {
+        assert_eq!(
+            top.width(),
+            self.top.width(),
+            "top trace has the wrong width"
+        );
+        assert_eq!(
+            bottom.width(),
+            self.bottom.width(),
+            "bottom trace has the wrong width"
+        );
+        assert!(
+            top.height() > 0 && bottom.height() > 0,
+            "both segments must be nonempty"
+        );
+        for column in self.bottom.periodic_columns() {
+            assert!(
+                top.height() % column.len() == 0,
+                "top segment height {} is not a multiple of the bottom periodic column length {}",
+                top.height(),
+                column.len()
+            );
+        }
+        let shared_width = self.shared_width::<F>();
+        let selectors = |is_top: bool, is_bottom_first: bool| {
+            [is_top, is_bottom_first].map(|b| if b { F::one() } else { F::zero() })
+        };
+
+        let mut values = Vec::with_capacity((top.height() + bottom.height()) * (shared_width + 2));
+        for r in 0..top.height() {
+            values.extend(padded_row(top, r, shared_width, F::zero()));
+            values.extend(selectors(true, false));
+        }
+        for r in 0..bottom.height() {
+            values.extend(padded_row(bottom, r, shared_width, F::zero()));
+            values.extend(selectors(false, r == 0));
+        }
+        RowMajorMatrix::new(values, shared_width + 2)
+    }
+
+    /// Splits a stacked trace back into the traces of `top` and `bottom`.
+    pub fn split_trace<T: Clone + Send + Sync + PartialEq>(
+        &self,
+        stacked: &RowMajorMatrix<T>,
+        one: &T,
+    ) -> (RowMajorMatrix<T>, RowMajorMatrix<T>)
+    where
+        A: BaseAir<T>,
+        B: BaseAir<T>,
+    {
+        let is_top_column = stacked.width() - 2;
+        let (mut top, mut bottom) = (Vec::new(), Vec::new());
+        for r in 0..stacked.height() {
+            let stacked_row = row(stacked, r);
+            if &stacked_row[is_top_column] == one {
+                top.extend_from_slice(&stacked_row[..self.top.width()]);
+            } else {
+                bottom.extend_from_slice(&stacked_row[..self.bottom.width()]);
+            }
+        }
+        (
+            RowMajorMatrix::new(top, self.top.width()),
+            RowMajorMatrix::new(bottom, self.bottom.width()),
+        )
+    }
+}
+
+impl<F, A, B> BaseAir<F> for VerticalAir<A, B>
+where
+    F: Clone + Send + Sync,
+    A: BaseAir<F>,
+    B: BaseAir<F>,
+{
+    fn width(&self) -> usize {
+        self.shared_width::<F>() + 2
+    }
+
+    fn window_size(&self) -> usize {
+        self.top.window_size().max(self.bottom.window_size())
+    }
+
+    fn permutation_width(&self) -> usize {
+        self.top
+            .permutation_width()
+            .max(self.bottom.permutation_width())
+    }
+
+    fn num_challenges(&self) -> usize {
+        self.top.num_challenges().max(self.bottom.num_challenges())
+    }
+
+    fn field_requirements(&self) -> Vec<FieldRequirement> {
+        let mut requirements = self.top.field_requirements();
+        requirements.extend(self.bottom.field_requirements());
+        requirements
+    }
+
+    fn periodic_columns(&self) -> Vec<Vec<F>> {
+        let mut columns = self.top.periodic_columns();
+        columns.extend(self.bottom.periodic_columns());
+        columns
+    }
+}
+
+impl<F, A, B> BaseAirWithPublicValues<F> for VerticalAir<A, B>
+where
+    F: Field + Arithmetic,
+    A: BaseAirWithPublicValues<F>,
+    B: BaseAirWithPublicValues<F>,
+{
+    fn num_public_values(&self) -> usize {
+        self.top
+            .num_public_values()
+            .max(self.bottom.num_public_values())
+    }
+}
+
+impl<F, EF, A, B> MultiPhaseAir<F, EF> for VerticalAir<A, B>
+where
+    F: Field + Arithmetic,
+    EF: ExtensionOf<F>,
+    A: MultiPhaseAir<F, EF>,
+    B: MultiPhaseAir<F, EF>,
+{
+    fn generate_permutation_trace(
+        &self,
+        main: &RowMajorMatrix<F>,
+        challenges: &[EF],
+    ) -> RowMajorMatrix<EF> {
+        let (top, bottom) = self.split_trace(main, &F::one());
+        let top = self
+            .top
+            .generate_permutation_trace(&top, &challenges[..self.top.num_challenges()]);
+        let bottom = self
+            .bottom
+            .generate_permutation_trace(&bottom, &challenges[..self.bottom.num_challenges()]);
+
+        let width = BaseAir::<F>::permutation_width(self);
+        let values = (0..top.height())
+            .flat_map(|r| padded_row(&top, r, width, EF::zero()))
+            .chain((0..bottom.height()).flat_map(|r| padded_row(&bottom, r, width, EF::zero())))
+            .collect();
+        RowMajorMatrix::new(values, width)
+    }
+}
+
+impl<AB, A, B> Air<AB> for VerticalAir<A, B>
+where
+    AB: AirBuilder,
+    A: BaseAir<AB::F> + for<'a> Air<SegmentAirBuilder<'a, AB>>,
+    B: BaseAir<AB::F> + for<'a> Air<SegmentAirBuilder<'a, AB>>,
+{
+    fn eval(&self, builder: &mut AB) {
+        let shared_width = self.shared_width::<AB::F>();
+        let main = builder.main();
+        let rows: Vec<_> = (0..main.height()).map(|r| row(&main, r)).collect();
+        let is_top: Vec<AB::Expr> = rows.iter().map(|r| r[shared_width].into()).collect();
+        let is_bottom_first: AB::Expr = rows[0][shared_width + 1].into();
+        let bottom_periodic_columns = self.bottom.periodic_columns();
+        let top_periodic = self.top.periodic_columns().len();
+        let bottom_periodic = top_periodic + bottom_periodic_columns.len();
+        // The lengths are powers of two, so the longest is a multiple of all the others.
+        let bottom_period = bottom_periodic_columns.iter().map(Vec::len).max();
+
+        builder.scope("selectors", |builder| {
+            let enters_top = (builder.one() - is_top[0].clone()) * is_top[1].clone();
+            let leaves_top = is_top[0].clone() * (builder.one() - is_top[1].clone());
+            let is_bottom_first_next: AB::Expr = rows[1][shared_width + 1].into();
+
+            builder.assert_bool(is_top[0].clone());
+            builder.when_first_row().assert_one(is_top[0].clone());
+            builder.when_last_row().assert_zero(is_top[0].clone());
+            builder.when_transition().assert_zero(enters_top);
+            builder
+                .when_first_row()
+                .assert_zero(is_bottom_first.clone());
+            builder
+                .when_transition()
+                .assert_eq(is_bottom_first_next, leaves_top);
+            if let Some(period) = bottom_period.filter(|&period| period > 1) {
+                let misaligned = builder.one() - builder.is_row_mod(0, period);
+                builder.assert_zero(is_bottom_first.clone() * misaligned);
+            }
+        });
+
+        builder.scope("top", |builder| {
+            let is_top_next = is_top[1].clone();
+            let mut top = SegmentAirBuilder {
+                main: columns(&main, 0..self.top.width()),
+                in_segment: is_top.clone(),
+                is_first_row: builder.is_first_row(),
+                is_last_row: builder.one() - is_top_next,
+                starts_trace: true,
+                periodic: 0..top_periodic,
+                permutation_width: self.top.permutation_width(),
+                num_challenges: self.top.num_challenges(),
+                inner: builder,
+            };
+            self.top.eval(&mut top);
+        });
+
+        builder.scope("bottom", |builder| {
+            let in_bottom = is_top.iter().map(|s| builder.one() - s.clone()).collect();
+            let mut bottom = SegmentAirBuilder {
+                main: columns(&main, 0..self.bottom.width()),
+                in_segment: in_bottom,
+                is_first_row: is_bottom_first.clone(),
+                is_last_row: builder.is_last_row(),
+                starts_trace: false,
+                periodic: top_periodic..bottom_periodic,
+                permutation_width: self.bottom.permutation_width(),
+                num_challenges: self.bottom.num_challenges(),
+                inner: builder,
+            };
+            self.bottom.eval(&mut bottom);
+        });
+    }
+}
+
+/// The builder `VerticalAir` evaluates each of its AIRs with: it shows the AIR its own columns,
+/// gates its constraints by its segment, and places its boundaries at the segment's edges.
+#[derive(Debug)]
+pub struct SegmentAirBuilder<'a, AB: AirBuilder> {
+    inner: &'a mut AB,
+    main: RowMajorMatrix<AB::Var>,
+    /// For each row of the window, whether it belongs to this segment.
+    in_segment: Vec<AB::Expr>,
+    is_first_row: AB::Expr,
+    is_last_row: AB::Expr,
+    /// Whether the segment starts on the first row of the trace, so that row indices need no offset.
+    starts_trace: bool,
+    periodic: Range<usize>,
+    /// The AIR's own auxiliary columns, which come first in the shared auxiliary trace.
+    permutation_width: usize,
+    num_challenges: usize,
+}
+
+impl<AB: AirBuilder> SegmentAirBuilder<'_, AB> {
+    /// Nonzero exactly on the rows of this segment.
+    pub fn in_segment(&self) -> AB::Expr {
+        self.in_segment[0].clone()
+    }
+
+    /// Row selectors count from the first row of the trace, so they only make sense on a segment
+    /// which starts there.
+    fn assert_starts_trace(&self, selector: fmt::Arguments<'_>) {
+        assert!(
+            self.starts_trace,
+            "row selectors are not supported in the bottom segment of a VerticalAir ({})",
+            selector
+        );
+    }
+}
+
+impl<AB: AirBuilder> AirBuilder for SegmentAirBuilder<'_, AB> {
+    type F = AB::F;
+    type Expr = AB::Expr;
+    type Var = AB::Var;
+    type M = RowMajorMatrix<AB::Var>;
+
+    fn main(&self) -> Self::M {
+        self.main.clone()
+    }
+
+    fn is_first_row(&self) -> Self::Expr {
+        self.is_first_row.clone()
+    }
+
+    fn is_last_row(&self) -> Self::Expr {
+        self.is_last_row.clone()
+    }
+
+    /// Windows which lie entirely within the segment. As the segment is a contiguous range of
+    /// rows, it is enough to check the last row of the window.
+    fn is_transition_window(&self, size: usize) -> Self::Expr {
+        self.inner.is_transition_window(size) * self.in_segment[size - 1].clone()
+    }
+
+    fn is_row(&self, row: usize) -> Self::Expr {
+        self.assert_starts_trace(format_args!("is_row({})", row));
+        self.inner.is_row(row)
+    }
+
+    fn is_row_mod(&self, residue: usize, period: usize) -> Self::Expr {
+        self.assert_starts_trace(format_args!("is_row_mod({}, {})", residue, period));
+        self.inner.is_row_mod(residue, period)
+    }
+
+    fn is_segment_start(&self, len: usize) -> Self::Expr {
+        self.assert_starts_trace(format_args!("is_segment_start({})", len));
+        self.inner.is_segment_start(len)
+    }
+
+    fn zero(&self) -> Self::Expr {
+        self.inner.zero()
+    }
+
+    fn one(&self) -> Self::Expr {
+        self.inner.one()
+    }
+
+    fn two(&self) -> Self::Expr {
+        self.inner.two()
+    }
+
+    fn from_u32(&self, val: u32) -> Self::Expr {
+        self.inner.from_u32(val)
+    }
+
+    fn require_field(&mut self, requirement: FieldRequirement) {
+        self.inner.require_field(requirement);
+    }
+
+    fn push_scope(&mut self, name: &str) {
+        self.inner.push_scope(name);
+    }
+
+    fn pop_scope(&mut self) {
+        self.inner.pop_scope();
+    }
+
+    fn assert_zero<I: Into<Self::Expr>>(&mut self, x: I) {
+        self.inner.assert_zero(self.in_segment() * x.into());
+    }
+}
+
+impl<AB: AirBuilderWithPublicValues> AirBuilderWithPublicValues for SegmentAirBuilder<'_, AB> {
+    type PublicVar = AB::PublicVar;
+
+    fn public_values(&self) -> &[Self::PublicVar] {
+        self.inner.public_values()
+    }
+}
+
+/// Preprocessed traces are not supported, so the AIR always sees an empty one.
+impl<AB: AirBuilder> PairBuilder for SegmentAirBuilder<'_, AB> {
+    fn preprocessed(&self) -> Self::M {
+        RowMajorMatrix::new(Vec::new(), 0)
+    }
+}
+
+impl<AB: PeriodicAirBuilder> PeriodicAirBuilder for SegmentAirBuilder<'_, AB> {
+    fn periodic_values(&self) -> &[Self::Var] {
+        &self.inner.periodic_values()[self.periodic.clone()]
+    }
+}
+
+impl<AB: ExtensionBuilder> ExtensionBuilder for SegmentAirBuilder<'_, AB> {
+    type EF = AB::EF;
+    type ExprEF = AB::ExprEF;
+    type VarEF = AB::VarEF;
+
+    fn lift<I: Into<Self::Expr>>(&self, x: I) -> Self::ExprEF {
+        self.inner.lift(x)
+    }
+
+    fn assert_zero_ext<I: Into<Self::ExprEF>>(&mut self, x: I) {
+        let in_segment = self.inner.lift(self.in_segment());
+        self.inner.assert_zero_ext(in_segment * x.into());
+    }
+}
+
+impl<AB: PermutationAirBuilder> PermutationAirBuilder for SegmentAirBuilder<'_, AB> {
+    type MP = RowMajorMatrix<AB::VarEF>;
+    type RandomVar = AB::RandomVar;
+
+    fn permutation(&self) -> Self::MP {
+        columns(&self.inner.permutation(), 0..self.permutation_width)
+    }
+
+    fn permutation_randomness(&self) -> &[Self::RandomVar] {
+        &self.inner.permutation_randomness()[..self.num_challenges]
+    }
+}
+
+/// Copies row `r` of `matrix`, padded with `fill` to `width` columns.
+fn padded_row<T: Clone + Send + Sync>(
+    matrix: &RowMajorMatrix<T>,
+    r: usize,
+    width: usize,
+    fill: T,
+) -> Vec<T> {
+    let mut padded = row(matrix, r);
+    padded.resize(width, fill);
+    padded
+}
@@ -4,7 +4,7 @@
 
 use alloc::vec;
 use alloc::vec::Vec;
-use core::ops::Mul;
+use core::ops::{Add, Mul};
 
 use icicle_core::traits::Arithmetic;
 use icicle_core::field::Field;
@@ -119,15 +119,29 @@ impl<F: Field + Arithmetic> VirtualPairCol<F> {
         )
     }
 
+    /// This column multiplied by `factor`.
+    #[must_use]
+    pub fn scale(&self, factor: F) -> Self {
+        Self {
+            column_weights: self
+                .column_weights
+                .iter()
+                .map(|&(column, weight)| (column, weight * factor))
+                .collect(),
+            constant: self.constant * factor,
+        }
+    }
+
     pub fn apply<Expr, Var>(&self, preprocessed: &[Var], main: &[Var]) -> Expr
     where
         F: Into<Expr>,
-        Expr: Field + Arithmetic + Mul<F, Output = Expr>,
+        Expr: Add<Expr, Output = Expr> + Mul<F, Output = Expr>,
         Var: Into<Expr> + Copy,
     {
-        let mut result = self.constant.clone().into();
+        let mut result = self.constant.into();
         for (column, weight) in self.column_weights.iter() {
-            result = result + column.get(preprocessed, main).into() * (*weight).clone();
+            let value: Expr = column.get(preprocessed, main).into();
+            result = result + value * *weight;
         }
         result
     }
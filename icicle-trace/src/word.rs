@@ -0,0 +1,365 @@
+//! Gadgets for 32- and 64-bit words.
+//!
+//! A word is stored either as little-endian 16-bit limbs (`Word`) or as little-endian bits
+//! (`WordBits`). Limb form is cheap to store and to add; bit form is needed for bitwise operations,
+//! rotations and shifts. Every gadget constrains a set of output columns, and comes with a
+//! `*_witness` function which computes the values to fill those columns with.
+//!
+//! Gadgets taking limbs assume each limb has already been range checked to `[0, 2^16)`, e.g. with
+//! `RangeCheckAir` or `limbs_from_bits`. Gadgets taking bits assume the bits have been checked to be
+//! boolean, e.g. with `assert_bits`. Outputs in limb form are range checked by the gadgets that
+//! produce them only where noted.
+
+use core::array;
+
+use icicle_core::field::Field;
+use icicle_core::traits::Arithmetic;
+
+use crate::air::AirBuilder;
+use crate::columns::AirColumns;
+use crate::field_requirement::FieldRequirement;
+use crate::utils::u64_to_limbs;
+
+/// The field size `add`, `sub` and `less_than` rely on, so that a limb sum with a carry cannot
+/// overflow.
+pub const WORD_ADD_FIELD_REQUIREMENT: FieldRequirement =
+    FieldRequirement::greater_than("word::add", 1 << 17);
+
+/// The field size the gadgets packing bits into limbs rely on.
+pub const WORD_PACK_FIELD_REQUIREMENT: FieldRequirement =
+    FieldRequirement::greater_than("word::pack", (1 << 16) - 1);
+
+/// A word as `LIMBS` little-endian 16-bit limbs.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, AirColumns)]
+pub struct Word<T, const LIMBS: usize> {
+    pub limbs: [T; LIMBS],
+}
+
+/// A word as `BITS` little-endian bits.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, AirColumns)]
+pub struct WordBits<T, const BITS: usize> {
+    pub bits: [T; BITS],
+}
+
+pub type Word32<T> = Word<T, 2>;
+pub type Word64<T> = Word<T, 4>;
+pub type Word32Bits<T> = WordBits<T, 32>;
+pub type Word64Bits<T> = WordBits<T, 64>;
+
+impl<F: Field + Arithmetic, const LIMBS: usize> Word<F, LIMBS> {
+    /// The limbs of the low `16 * LIMBS` bits of `value`, with any limbs past its 64 bits zero.
+    pub fn from_u64(value: u64) -> Self {
+        Self {
+            limbs: u64_to_limbs::<F, 16, LIMBS>(value),
+        }
+    }
+}
+
+impl<F: Field + Arithmetic, const BITS: usize> WordBits<F, BITS> {
+    /// The low `BITS` bits of `value`, with any bits past its 64 bits zero.
+    pub fn from_u64(value: u64) -> Self {
+        Self {
+            bits: u64_to_limbs::<F, 1, BITS>(value),
+        }
+    }
+}
+
+/// Asserts that every bit of `word` is boolean.
+#[track_caller]
+pub fn assert_bits<AB: AirBuilder, const BITS: usize>(
+    builder: &mut AB,
+    word: &WordBits<AB::Var, BITS>,
+) {
+    word.bits.iter().for_each(|&bit| builder.assert_bool(bit));
+}
+
+/// Asserts that `limbs` and `bits` hold the same word, and that the bits are boolean. This also
+/// range checks the limbs.
+///
+/// Fill both with `Word::from_u64` and `WordBits::from_u64`. Requires `P >= 2^16`.
+#[track_caller]
+pub fn limbs_from_bits<AB: AirBuilder, const LIMBS: usize, const BITS: usize>(
+    builder: &mut AB,
+    limbs: &Word<AB::Var, LIMBS>,
+    bits: &WordBits<AB::Var, BITS>,
+) {
+    assert_eq!(BITS, 16 * LIMBS, "limb and bit forms have different widths");
+    builder.require_field(WORD_PACK_FIELD_REQUIREMENT);
+    assert_bits(builder, bits);
+    for (limb, chunk) in limbs.limbs.iter().zip(bits.bits.chunks(16)) {
+        let packed = builder.pack_bits_le(chunk.iter().copied());
+        builder.assert_eq(*limb, packed);
+    }
+}
+
+/// Asserts that `sum = a + b mod 2^(16 * LIMBS)`.
+///
+/// `carries[i]` is the carry out of limb `i`, and is asserted to be boolean; the last one is the
+/// carry out of the whole word. Fill `sum` and `carries` with `add_witness`. All limbs must be range
+/// checked, and `P > 2^17`.
+#[track_caller]
+pub fn add<AB: AirBuilder, const LIMBS: usize>(
+    builder: &mut AB,
+    sum: &Word<AB::Var, LIMBS>,
+    a: &Word<AB::Var, LIMBS>,
+    b: &Word<AB::Var, LIMBS>,
+    carries: &[AB::Var; LIMBS],
+) {
+    builder.require_field(WORD_ADD_FIELD_REQUIREMENT);
+
+    // Limb by limb, a[i] + b[i] + carries[i - 1] = sum[i] + 2^16 carries[i]. Both sides are below
+    // 2^17 over the integers, so with P > 2^17 the equation cannot wrap around.
+    let two_16 = builder.from_u32(1 << 16);
+    let mut carry_in = builder.zero();
+    for (i, &carry) in carries.iter().enumerate() {
+        builder.assert_bool(carry);
+        builder.assert_eq(
+            a.limbs[i].into() + b.limbs[i].into() + carry_in,
+            sum.limbs[i].into() + two_16.clone() * carry.into(),
+        );
+        carry_in = carry.into();
+    }
+}
+
+/// Computes the `sum` and `carries` columns of `add`.
+///
+/// With more than four limbs the sum can exceed 64 bits, so it is computed limb by limb.
+pub fn add_witness<F: Field + Arithmetic, const LIMBS: usize>(
+    a: u64,
+    b: u64,
+) -> (Word<F, LIMBS>, [F; LIMBS]) {
+    let limb = |x: u64, i: usize| x.checked_shr(16 * i as u32).unwrap_or(0) & 0xFFFF;
+    let mut carry = 0;
+    let mut sum = [0; LIMBS];
+    let carries = array::from_fn(|i| {
+        let limb_sum = limb(a, i) + limb(b, i) + carry;
+        sum[i] = limb_sum & 0xFFFF;
+        carry = limb_sum >> 16;
+        F::from_u32(carry as u32)
+    });
+    let limbs = sum.map(|limb| F::from_u32(limb as u32));
+    (Word { limbs }, carries)
+}
+
+/// Asserts that `diff = a - b mod 2^(16 * LIMBS)`.
+///
+/// This is `add` checking `diff + b = a`, so `borrows` are its carries; the last one is set exactly
+/// when `a < b`. Fill `diff` and `borrows` with `sub_witness`. The same preconditions as `add`.
+#[track_caller]
+pub fn sub<AB: AirBuilder, const LIMBS: usize>(
+    builder: &mut AB,
+    diff: &Word<AB::Var, LIMBS>,
+    a: &Word<AB::Var, LIMBS>,
+    b: &Word<AB::Var, LIMBS>,
+    borrows: &[AB::Var; LIMBS],
+) {
+    add(builder, a, diff, b, borrows);
+}
+
+/// Computes the `diff` and `borrows` columns of `sub`.
+pub fn sub_witness<F: Field + Arithmetic, const LIMBS: usize>(
+    a: u64,
+    b: u64,
+) -> (Word<F, LIMBS>, [F; LIMBS]) {
+    let diff = a.wrapping_sub(b) & word_mask(LIMBS);
+    let (_, borrows) = add_witness::<F, LIMBS>(diff, b);
+    (Word::from_u64(diff), borrows)
+}
+
+/// Returns an expression which is one if `a < b` and zero otherwise.
+///
+/// This computes `a - b` with `sub` and reads off the final borrow, so `diff` and `borrows` are
+/// filled with `less_than_witness`. `diff` must be range checked like the inputs.
+#[track_caller]
+pub fn less_than<AB: AirBuilder, const LIMBS: usize>(
+    builder: &mut AB,
+    a: &Word<AB::Var, LIMBS>,
+    b: &Word<AB::Var, LIMBS>,
+    diff: &Word<AB::Var, LIMBS>,
+    borrows: &[AB::Var; LIMBS],
+) -> AB::Expr {
+    sub(builder, diff, a, b, borrows);
+    borrows[LIMBS - 1].into()
+}
+
+/// Computes the `diff` and `borrows` columns of `less_than`.
+pub fn less_than_witness<F: Field + Arithmetic, const LIMBS: usize>(
+    a: u64,
+    b: u64,
+) -> (Word<F, LIMBS>, [F; LIMBS]) {
+    sub_witness(a, b)
+}
+
+/// Asserts that `out = a & b`.
+#[track_caller]
+pub fn and<AB: AirBuilder, const BITS: usize>(
+    builder: &mut AB,
+    out: &WordBits<AB::Var, BITS>,
+    a: &WordBits<AB::Var, BITS>,
+    b: &WordBits<AB::Var, BITS>,
+) {
+    for i in 0..BITS {
+        builder.assert_eq(out.bits[i], a.bits[i].into() * b.bits[i].into());
+    }
+}
+
+/// Computes the `out` column of `and`.
+pub fn and_witness<F: Field + Arithmetic, const BITS: usize>(a: u64, b: u64) -> WordBits<F, BITS> {
+    WordBits::from_u64(a & b)
+}
+
+/// Asserts that `out = a | b`.
+#[track_caller]
+pub fn or<AB: AirBuilder, const BITS: usize>(
+    builder: &mut AB,
+    out: &WordBits<AB::Var, BITS>,
+    a: &WordBits<AB::Var, BITS>,
+    b: &WordBits<AB::Var, BITS>,
+) {
+    for i in 0..BITS {
+        let (x, y) = (a.bits[i].into(), b.bits[i].into());
+        builder.assert_eq(out.bits[i], x.clone() + y.clone() - x * y);
+    }
+}
+
+/// Computes the `out` column of `or`.
+pub fn or_witness<F: Field + Arithmetic, const BITS: usize>(a: u64, b: u64) -> WordBits<F, BITS> {
+    WordBits::from_u64(a | b)
+}
+
+/// Asserts that `out = a ^ b`.
+#[track_caller]
+pub fn xor<AB: AirBuilder, const BITS: usize>(
+    builder: &mut AB,
+    out: &WordBits<AB::Var, BITS>,
+    a: &WordBits<AB::Var, BITS>,
+    b: &WordBits<AB::Var, BITS>,
+) {
+    for i in 0..BITS {
+        let xor = builder.xor(a.bits[i], b.bits[i]);
+        builder.assert_eq(out.bits[i], xor);
+    }
+}
+
+/// Computes the `out` column of `xor`.
+pub fn xor_witness<F: Field + Arithmetic, const BITS: usize>(a: u64, b: u64) -> WordBits<F, BITS> {
+    WordBits::from_u64(a ^ b)
+}
+
+/// Asserts that `out = !a`.
+#[track_caller]
+pub fn not<AB: AirBuilder, const BITS: usize>(
+    builder: &mut AB,
+    out: &WordBits<AB::Var, BITS>,
+    a: &WordBits<AB::Var, BITS>,
+) {
+    for i in 0..BITS {
+        let not = builder.one() - a.bits[i].into();
+        builder.assert_eq(out.bits[i], not);
+    }
+}
+
+/// Computes the `out` column of `not`.
+pub fn not_witness<F: Field + Arithmetic, const BITS: usize>(a: u64) -> WordBits<F, BITS> {
+    WordBits::from_u64(!a)
+}
+
+/// Asserts that the limbs `out` hold `a` rotated right by `n` bits. This also range checks `out`.
+///
+/// Fill `out` with `rotate_right_witness`. Requires `P >= 2^16`.
+#[track_caller]
+pub fn rotate_right<AB: AirBuilder, const LIMBS: usize, const BITS: usize>(
+    builder: &mut AB,
+    out: &Word<AB::Var, LIMBS>,
+    a: &WordBits<AB::Var, BITS>,
+    n: usize,
+) {
+    assert_eq!(BITS, 16 * LIMBS, "limb and bit forms have different widths");
+    assert_packed(builder, out, |i| Some(a.bits[(i + n) % BITS]));
+}
+
+/// Computes the `out` column of `rotate_right`.
+pub fn rotate_right_witness<F: Field + Arithmetic, const LIMBS: usize>(
+    a: u64,
+    n: usize,
+) -> Word<F, LIMBS> {
+    let width = 16 * LIMBS;
+    let a = a & word_mask(LIMBS);
+    let n = n % width;
+    if n == 0 {
+        return Word::from_u64(a);
+    }
+    Word::from_u64((a >> n) | (a << (width - n)))
+}
+
+/// Asserts that the limbs `out` hold `a` shifted right by `n` bits. This also range checks `out`.
+///
+/// Fill `out` with `shift_right_witness`. Requires `P >= 2^16`.
+#[track_caller]
+pub fn shift_right<AB: AirBuilder, const LIMBS: usize, const BITS: usize>(
+    builder: &mut AB,
+    out: &Word<AB::Var, LIMBS>,
+    a: &WordBits<AB::Var, BITS>,
+    n: usize,
+) {
+    assert_eq!(BITS, 16 * LIMBS, "limb and bit forms have different widths");
+    assert_packed(builder, out, |i| a.bits.get(i + n).copied());
+}
+
+/// Computes the `out` column of `shift_right`.
+pub fn shift_right_witness<F: Field + Arithmetic, const LIMBS: usize>(
+    a: u64,
+    n: usize,
+) -> Word<F, LIMBS> {
+    let a = a & word_mask(LIMBS);
+    Word::from_u64(a.checked_shr(n as u32).unwrap_or(0))
+}
+
+/// Asserts that the limbs `out` hold `a` shifted left by `n` bits. This also range checks `out`.
+///
+/// Fill `out` with `shift_left_witness`. Requires `P >= 2^16`.
+#[track_caller]
+pub fn shift_left<AB: AirBuilder, const LIMBS: usize, const BITS: usize>(
+    builder: &mut AB,
+    out: &Word<AB::Var, LIMBS>,
+    a: &WordBits<AB::Var, BITS>,
+    n: usize,
+) {
+    assert_eq!(BITS, 16 * LIMBS, "limb and bit forms have different widths");
+    assert_packed(builder, out, |i| i.checked_sub(n).map(|j| a.bits[j]));
+}
+
+/// Computes the `out` column of `shift_left`.
+pub fn shift_left_witness<F: Field + Arithmetic, const LIMBS: usize>(
+    a: u64,
+    n: usize,
+) -> Word<F, LIMBS> {
+    Word::from_u64(a.checked_shl(n as u32).unwrap_or(0))
+}
+
+/// Asserts that each limb of `out` packs the bits `bit(16 * limb + j)`, with missing bits as zero.
+#[track_caller]
+fn assert_packed<AB: AirBuilder, const LIMBS: usize>(
+    builder: &mut AB,
+    out: &Word<AB::Var, LIMBS>,
+    bit: impl Fn(usize) -> Option<AB::Var>,
+) {
+    builder.require_field(WORD_PACK_FIELD_REQUIREMENT);
+    for (limb, &value) in out.limbs.iter().enumerate() {
+        let bits: [AB::Expr; 16] =
+            array::from_fn(|j| bit(16 * limb + j).map_or(builder.zero(), Into::into));
+        let packed = builder.pack_bits_le(bits.into_iter());
+        builder.assert_eq(value, packed);
+    }
+}
+
+/// The mask of a word with `limbs` 16-bit limbs.
+const fn word_mask(limbs: usize) -> u64 {
+    if limbs >= 4 {
+        u64::MAX
+    } else {
+        (1 << (16 * limbs)) - 1
+    }
+}
@@ -0,0 +1,74 @@
+use core::borrow::{Borrow, BorrowMut};
+
+use icicle_trace::columns::column_map;
+use icicle_trace::word::Word32;
+use icicle_trace::AirColumns;
+
+#[derive(Debug, AirColumns)]
+#[repr(C)]
+pub struct RoundCols<T> {
+    pub state: [[T; 2]; 3],
+    pub output: Word32<T>,
+}
+
+#[derive(Debug, AirColumns)]
+#[repr(C)]
+pub struct ExampleCols<T, const ROUNDS: usize> {
+    pub is_real: T,
+    pub rounds: [RoundCols<T>; ROUNDS],
+    pub digest: [T; 2],
+}
+
+type Cols<T> = ExampleCols<T, 2>;
+
+const NUM_COLS: usize = <Cols<u8> as AirColumns<u8>>::NUM_COLUMNS;
+const COL_MAP: Cols<usize> = column_map();
+
+#[test]
+fn column_count_matches_the_layout() {
+    assert_eq!(<RoundCols<u64> as AirColumns<u64>>::NUM_COLUMNS, 8);
+    assert_eq!(NUM_COLS, 19);
+    assert_eq!(NUM_COLS, size_of::<Cols<u8>>());
+}
+
+#[test]
+fn column_map_holds_indices() {
+    assert_eq!(COL_MAP.is_real, 0);
+    assert_eq!(COL_MAP.rounds[0].state[1][0], 3);
+    assert_eq!(COL_MAP.rounds[1].output.limbs, [15, 16]);
+    assert_eq!(COL_MAP.digest, [17, 18]);
+}
+
+#[test]
+fn column_names_are_hierarchical() {
+    let names = <Cols<u32> as AirColumns<u32>>::column_names();
+    assert_eq!(names.len(), NUM_COLS);
+    assert_eq!(names[0], "is_real");
+    assert_eq!(
+        names[COL_MAP.rounds[0].state[2][1]],
+        "rounds[0].state[2][1]"
+    );
+    assert_eq!(
+        names[COL_MAP.rounds[1].output.limbs[1]],
+        "rounds[1].output.limbs[1]"
+    );
+    assert_eq!(names[18], "digest[1]");
+}
+
+#[test]
+fn rows_borrow_as_columns() {
+    let mut row: Vec<u32> = (0..NUM_COLS as u32).collect();
+    let cols: &mut Cols<u32> = row.as_mut_slice().borrow_mut();
+    cols.rounds[1].state[0][1] = 100;
+    assert_eq!(row[COL_MAP.rounds[1].state[0][1]], 100);
+
+    let cols: &Cols<u32> = row.as_slice().borrow();
+    assert_eq!(cols.digest, [17, 18]);
+}
+
+#[test]
+#[should_panic(expected = "row has the wrong width")]
+fn short_rows_are_rejected() {
+    let row = [0u32; 5];
+    let _: &Cols<u32> = row.as_slice().borrow();
+}
@@ -0,0 +1,112 @@
+use icicle_babybear::field::ScalarField as Fr;
+use icicle_core::bignum::BigNum;
+use icicle_trace::{
+    check_bus_balance, BaseAir, BaseAirWithPublicValues, BusInstance, Interaction, InteractionAir,
+    InteractionKind, VirtualPairCol,
+};
+use p3_matrix::dense::RowMajorMatrix;
+
+/// Sends the pair `(a, b)` in columns 0 and 1 with the multiplicity in column 2.
+pub struct ProducerAir {}
+
+impl BaseAir<Fr> for ProducerAir {
+    fn width(&self) -> usize {
+        3
+    }
+}
+
+impl BaseAirWithPublicValues<Fr> for ProducerAir {}
+
+impl InteractionAir<Fr> for ProducerAir {
+    fn interactions(&self) -> Vec<Interaction<Fr>> {
+        vec![Interaction::send(
+            0,
+            vec![
+                VirtualPairCol::single_main(0),
+                VirtualPairCol::single_main(1),
+            ],
+            VirtualPairCol::single_main(2),
+        )]
+    }
+}
+
+/// Receives the pair `(a, b)` in columns 0 and 1 once per row.
+pub struct ConsumerAir {}
+
+impl BaseAir<Fr> for ConsumerAir {
+    fn width(&self) -> usize {
+        2
+    }
+}
+
+impl BaseAirWithPublicValues<Fr> for ConsumerAir {}
+
+impl InteractionAir<Fr> for ConsumerAir {
+    fn interactions(&self) -> Vec<Interaction<Fr>> {
+        vec![Interaction::receive(
+            0,
+            vec![
+                VirtualPairCol::single_main(0),
+                VirtualPairCol::single_main(1),
+            ],
+            VirtualPairCol::constant(Fr::one()),
+        )]
+    }
+}
+
+fn matrix(rows: &[&[u32]], width: usize) -> RowMajorMatrix<Fr> {
+    let values = rows
+        .iter()
+        .flat_map(|row| row.iter().map(|&v| Fr::from_u32(v)))
+        .collect();
+    RowMajorMatrix::new(values, width)
+}
+
+#[test]
+fn matching_sends_and_receives_balance() {
+    let producer = matrix(&[&[1, 2, 2], &[3, 4, 1], &[5, 6, 0], &[0, 0, 0]], 3);
+    let consumer = matrix(&[&[3, 4], &[1, 2], &[1, 2], &[0, 0]], 2);
+    let producer_padding = matrix(&[&[0, 0, 1]], 3);
+
+    let instances = [
+        BusInstance::new(&ProducerAir {}, &producer, &[]),
+        BusInstance::new(&ProducerAir {}, &producer_padding, &[]),
+        BusInstance::new(&ConsumerAir {}, &consumer, &[]),
+    ];
+    check_bus_balance(&instances).unwrap();
+}
+
+#[test]
+fn unbalanced_tuple_is_reported() {
+    let producer = matrix(&[&[1, 2, 1], &[3, 4, 1]], 3);
+    let consumer = matrix(&[&[3, 4], &[7, 8]], 2);
+
+    let instances = [
+        BusInstance::new(&ProducerAir {}, &producer, &[]),
+        BusInstance::new(&ConsumerAir {}, &consumer, &[]),
+    ];
+    let error = check_bus_balance(&instances).unwrap_err();
+
+    assert_eq!(error.imbalances.len(), 2);
+    let sent = error
+        .imbalances
+        .iter()
+        .find(|imbalance| imbalance.tuple == [Fr::from_u32(1), Fr::from_u32(2)])
+        .unwrap();
+    assert_eq!(sent.bus, 0);
+    assert_eq!(sent.net_multiplicity, Fr::one());
+    assert_eq!(sent.contributions.len(), 1);
+    assert_eq!(sent.contributions[0].row, 0);
+    assert_eq!(sent.contributions[0].kind, InteractionKind::Send);
+    assert!(sent.contributions[0].air_name.ends_with("ProducerAir"));
+
+    let received = error
+        .imbalances
+        .iter()
+        .find(|imbalance| imbalance.tuple == [Fr::from_u32(7), Fr::from_u32(8)])
+        .unwrap();
+    assert_eq!(received.net_multiplicity, Fr::zero() - Fr::one());
+    assert_eq!(received.contributions[0].row, 1);
+    assert_eq!(received.contributions[0].kind, InteractionKind::Receive);
+    assert!(received.contributions[0].air_name.ends_with("ConsumerAir"));
+}
@@ -0,0 +1,156 @@
+use icicle_babybear::field::{ExtensionField as EF, ScalarField as Fr};
+use icicle_core::bignum::BigNum;
+use icicle_trace::{
+    check_constraints, check_constraints_with_extension, get_symbolic_constraints, Air, AirBuilder,
+    AirBuilderWithPublicValues, BaseAir, BaseAirWithPublicValues, ConstraintError,
+    ConstraintFailure, ExtensionBuilder, ExtensionOf, FieldRequirement,
+};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+
+/// Holds `(x, x^2)` on each row, with `x` counting up from a public value.
+pub struct SquaresAir {}
+
+impl BaseAir<Fr> for SquaresAir {
+    fn width(&self) -> usize {
+        2
+    }
+}
+
+impl BaseAirWithPublicValues<Fr> for SquaresAir {
+    fn num_public_values(&self) -> usize {
+        1
+    }
+}
+
+impl<AB: ExtensionBuilder<F = Fr> + AirBuilderWithPublicValues> Air<AB> for SquaresAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0).expect("row_slice returned None").to_vec();
+        let next = main.row_slice(1).expect("row_slice returned None").to_vec();
+        let start = builder.public_values()[0].clone().into();
+
+        builder.when_first_row().assert_eq(local[0], start);
+        builder.scope("counter", |builder| {
+            builder
+                .when_transition()
+                .assert_eq(next[0], local[0] + Fr::one());
+        });
+        builder.scope("square", |builder| {
+            let square = builder.lift(local[1] - local[0] * local[0]);
+            builder.assert_zero_ext(square);
+        });
+    }
+}
+
+fn trace(square: impl Fn(u32) -> u32) -> RowMajorMatrix<Fr> {
+    let values = (3..7)
+        .flat_map(|x| [Fr::from_u32(x), Fr::from_u32(square(x))])
+        .collect();
+    RowMajorMatrix::new(values, 2)
+}
+
+#[test]
+fn valid_trace_passes() {
+    let result = check_constraints_with_extension::<_, EF, _>(
+        &SquaresAir {},
+        &trace(|x| x * x),
+        &[Fr::from_u32(3)],
+    );
+    assert!(result.is_ok());
+}
+
+#[test]
+fn failure_names_the_row_and_constraint() {
+    let main = trace(|x| x * x);
+    let result =
+        check_constraints_with_extension::<_, EF, _>(&SquaresAir {}, &main, &[Fr::from_u32(2)]);
+    let Err(ConstraintError::Constraint(error)) = result else {
+        panic!("the first row does not start at 2");
+    };
+    assert_eq!(error.air_name, "check_constraints::SquaresAir");
+    assert_eq!((error.row, error.constraint_index), (0, 0));
+    // The first-row filter multiplies the constraint, so it fails as a nonzero value.
+    assert_eq!(error.failure, ConstraintFailure::NonZero(Fr::one()));
+    assert_eq!(error.scope, "");
+    assert!(error
+        .to_string()
+        .starts_with("constraints had nonzero value on row 0 at "));
+    assert_eq!(get_symbolic_constraints(&SquaresAir {}).len(), 2);
+}
+
+#[test]
+fn extension_failures_are_reported() {
+    let main = trace(|x| if x == 5 { 26 } else { x * x });
+    let result =
+        check_constraints_with_extension::<_, EF, _>(&SquaresAir {}, &main, &[Fr::from_u32(3)]);
+    let Err(ConstraintError::Constraint(error)) = result else {
+        panic!("row 2 is not a square");
+    };
+    assert_eq!((error.row, error.constraint_index), (2, 0));
+    assert_eq!(error.scope, "square");
+    assert_eq!(
+        error.failure,
+        ConstraintFailure::NonZeroExtension(EF::from_base(Fr::one()))
+    );
+    assert!(error
+        .to_string()
+        .starts_with("extension constraints had nonzero value on row 2 in `square` at "));
+}
+
+/// An AIR without constraints whose soundness relies on a field below 2^30.
+pub struct SmallFieldAir {}
+
+impl BaseAir<Fr> for SmallFieldAir {
+    fn width(&self) -> usize {
+        1
+    }
+
+    fn field_requirements(&self) -> Vec<FieldRequirement> {
+        vec![FieldRequirement::between("SmallFieldAir", 1 << 17, 1 << 30)]
+    }
+}
+
+impl BaseAirWithPublicValues<Fr> for SmallFieldAir {}
+
+impl<AB: AirBuilder<F = Fr>> Air<AB> for SmallFieldAir {
+    fn eval(&self, _builder: &mut AB) {}
+}
+
+#[test]
+fn shape_mismatches_are_reported() {
+    let main = RowMajorMatrix::new(vec![Fr::one(); 6], 3);
+    let error =
+        check_constraints_with_extension::<_, EF, _>(&SquaresAir {}, &main, &[Fr::from_u32(3)])
+            .unwrap_err();
+    assert_eq!(
+        error,
+        ConstraintError::Shape {
+            mismatch: "main trace has the wrong width",
+            expected: 2,
+            found: 3,
+        }
+    );
+
+    let error =
+        check_constraints_with_extension::<_, EF, _>(&SquaresAir {}, &trace(|x| x * x), &[])
+            .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "wrong number of public values: expected 1, found 0"
+    );
+}
+
+#[test]
+fn air_field_requirements_are_reported() {
+    let main = RowMajorMatrix::new(vec![Fr::zero(); 4], 1);
+    let result = check_constraints(&SmallFieldAir {}, &main, &[]);
+    let Err(ConstraintError::FieldRequirement {
+        error, location, ..
+    }) = result
+    else {
+        panic!("BabyBear is above 2^30");
+    };
+    assert_eq!(error.requirement.source, "SmallFieldAir");
+    assert_eq!(location, None);
+}
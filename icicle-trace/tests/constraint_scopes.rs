@@ -0,0 +1,44 @@
+use icicle_babybear::field::ScalarField as Fr;
+use icicle_trace::{
+    get_named_symbolic_constraints, Air, AirBuilder, BaseAir, BaseAirWithPublicValues,
+};
+use p3_matrix::Matrix;
+
+/// Two boolean columns, one constrained inside nested scopes.
+pub struct ScopedAir {}
+
+impl<F> BaseAir<F> for ScopedAir {
+    fn width(&self) -> usize {
+        2
+    }
+}
+
+impl BaseAirWithPublicValues<Fr> for ScopedAir {}
+
+impl<AB: AirBuilder> Air<AB> for ScopedAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0).expect("row_slice returned None");
+        let (a, b) = (local[0], local[1]);
+
+        builder.assert_bool(a);
+        builder.scope("outer", |builder| {
+            builder.scope("inner", |builder| {
+                builder.when_first_row().assert_bool(b);
+            });
+        });
+    }
+}
+
+#[test]
+fn constraints_keep_scope_and_location() {
+    let constraints = get_named_symbolic_constraints::<Fr, ScopedAir>(&ScopedAir {});
+    assert_eq!(constraints.len(), 2);
+
+    assert_eq!(constraints[0].name, "");
+    assert_eq!(constraints[1].name, "outer/inner");
+    for constraint in &constraints {
+        assert_eq!(constraint.location.file(), file!());
+    }
+    assert!(constraints[0].location.line() < constraints[1].location.line());
+}
@@ -0,0 +1,93 @@
+use icicle_babybear::field::ScalarField as Fr;
+use icicle_trace::{
+    get_symbolic_constraints_with_max_degree, Air, AirBuilder, BaseAir, BaseAirWithPublicValues,
+    ExtensionBuilder,
+};
+use p3_matrix::Matrix;
+
+/// One boolean constraint and one ternary constraint, the latter of degree 3.
+pub struct MixedDegreeAir {}
+
+impl<F> BaseAir<F> for MixedDegreeAir {
+    fn width(&self) -> usize {
+        2
+    }
+}
+
+impl BaseAirWithPublicValues<Fr> for MixedDegreeAir {}
+
+impl<AB: AirBuilder> Air<AB> for MixedDegreeAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0).expect("row_slice returned None");
+        let (a, b) = (local[0], local[1]);
+
+        builder.assert_bool(a);
+        builder.scope("digits", |builder| builder.assert_tern(b));
+    }
+}
+
+/// A quadratic base constraint and a quintic extension constraint, as in a product argument.
+pub struct HighDegreeExtensionAir {}
+
+impl<F> BaseAir<F> for HighDegreeExtensionAir {
+    fn width(&self) -> usize {
+        1
+    }
+}
+
+impl BaseAirWithPublicValues<Fr> for HighDegreeExtensionAir {}
+
+impl<AB: ExtensionBuilder> Air<AB> for HighDegreeExtensionAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let a = main.row_slice(0).expect("row_slice returned None")[0];
+
+        builder.assert_bool(a);
+        let x = builder.lift(a);
+        let x4 = x.clone() * x.clone() * x.clone() * x.clone();
+        builder.scope("product", |builder| {
+            builder.assert_eq_ext(x4 * x.clone(), x)
+        });
+    }
+}
+
+#[test]
+fn constraints_within_bound() {
+    let constraints =
+        get_symbolic_constraints_with_max_degree::<Fr, MixedDegreeAir>(&MixedDegreeAir {}, 3)
+            .expect("all constraints have degree at most 3");
+    assert_eq!(constraints.len(), 2);
+}
+
+#[test]
+fn constraints_above_bound_are_reported() {
+    let err = get_symbolic_constraints_with_max_degree::<Fr, MixedDegreeAir>(&MixedDegreeAir {}, 2)
+        .unwrap_err();
+    assert_eq!(err.max_degree, 2);
+    assert_eq!(err.violations.len(), 1);
+
+    let violation = &err.violations[0];
+    assert_eq!(violation.index, 1);
+    assert_eq!(violation.degree, 3);
+    assert_eq!(violation.name, "digits");
+    assert_eq!(violation.location.file(), file!());
+}
+
+#[test]
+fn extension_constraints_above_bound_are_reported() {
+    let err = get_symbolic_constraints_with_max_degree::<Fr, HighDegreeExtensionAir>(
+        &HighDegreeExtensionAir {},
+        3,
+    )
+    .unwrap_err();
+    assert_eq!(err.violations.len(), 1);
+
+    let violation = &err.violations[0];
+    assert!(violation.extension);
+    assert_eq!((violation.index, violation.degree), (0, 5));
+    assert_eq!(violation.name, "product");
+    assert!(err
+        .to_string()
+        .contains("extension constraint 0 has degree 5 in `product`"));
+}
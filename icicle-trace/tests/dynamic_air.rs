@@ -0,0 +1,79 @@
+use icicle_babybear::field::ScalarField as Fr;
+use icicle_core::bignum::BigNum;
+use icicle_trace::{
+    check_constraints, get_max_constraint_degree, get_symbolic_constraints, BaseAir,
+    ConstraintError, DynamicAir, SymbolicExpression,
+};
+use p3_matrix::dense::RowMajorMatrix;
+
+/// The Fibonacci AIR, from public values `a`, `b` to `x`, built at runtime.
+fn fibonacci_air() -> DynamicAir<Fr> {
+    let mut air = DynamicAir::new(vec!["left".into(), "right".into()], 3);
+    let (left, right) = (air.local("left"), air.local("right"));
+    let (next_left, next_right) = (air.next("left"), air.next("right"));
+    let (a, b, x) = (air.public(0), air.public(1), air.public(2));
+
+    air.assert_zero(SymbolicExpression::IsFirstRow * (left - a));
+    air.assert_zero(SymbolicExpression::IsFirstRow * (right - b));
+    air.assert_zero(SymbolicExpression::IsTransition * (next_left - right));
+    air.assert_zero(SymbolicExpression::IsTransition * (next_right - (left + right)));
+    air.assert_zero(SymbolicExpression::IsLastRow * (right - x));
+    air
+}
+
+fn check(
+    air: DynamicAir<Fr>,
+    main: RowMajorMatrix<Fr>,
+    public_values: &[u32],
+) -> Result<(), ConstraintError<Fr, Fr>> {
+    let public_values: Vec<_> = public_values.iter().copied().map(Fr::from_u32).collect();
+    check_constraints(&air, &main, &public_values)
+}
+
+fn fibonacci_trace(a: u32, b: u32, n: usize) -> RowMajorMatrix<Fr> {
+    let mut values = vec![a, b];
+    for r in 1..n {
+        let (left, right) = (values[2 * r - 2], values[2 * r - 1]);
+        values.extend([right, left + right]);
+    }
+    RowMajorMatrix::new(values.into_iter().map(Fr::from_u32).collect(), 2)
+}
+
+#[test]
+fn runtime_fibonacci_passes() {
+    assert_eq!(
+        check(fibonacci_air(), fibonacci_trace(0, 1, 8), &[0, 1, 21]),
+        Ok(())
+    );
+}
+
+#[test]
+fn runtime_fibonacci_checks_the_public_output() {
+    let error = check(fibonacci_air(), fibonacci_trace(0, 1, 8), &[0, 1, 20]).unwrap_err();
+    assert!(error
+        .to_string()
+        .starts_with("constraints had nonzero value on row 7"));
+}
+
+#[test]
+fn symbolic_constraints_round_trip() {
+    let air = fibonacci_air();
+    assert_eq!(get_symbolic_constraints(&air), air.constraints());
+    assert_eq!(get_max_constraint_degree(&air), 2);
+}
+
+#[test]
+fn window_grows_with_the_largest_offset() {
+    let mut air = DynamicAir::<Fr>::new(vec!["x".into()], 0);
+    assert_eq!(air.window_size(), 2);
+    let (x, x2) = (air.local("x"), air.main("x", 2));
+    air.assert_zero(SymbolicExpression::IsTransitionWindow(3) * (x2 - x));
+    assert_eq!(air.window_size(), 3);
+    assert_eq!(air.column("x"), Some(0));
+}
+
+#[test]
+#[should_panic(expected = "unknown column `middle`")]
+fn unknown_columns_are_rejected() {
+    fibonacci_air().local("middle");
+}
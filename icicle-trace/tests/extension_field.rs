@@ -0,0 +1,108 @@
+use icicle_babybear::field::{ExtensionField as EF, ScalarField as Fr};
+use icicle_core::bignum::BigNum;
+use icicle_trace::{
+    check_constraints_with_extension, get_log_quotient_degree, get_max_constraint_degree, Air,
+    BaseAir, BaseAirWithPublicValues, ConstraintError, ConstraintFailure, ExtensionBuilder,
+    ExtensionOf,
+};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+
+/// Requires the single column, lifted to the extension, to equal `value`.
+pub struct ConstantAir {
+    value: u32,
+}
+
+impl<F> BaseAir<F> for ConstantAir {
+    fn width(&self) -> usize {
+        1
+    }
+}
+
+impl BaseAirWithPublicValues<Fr> for ConstantAir {}
+
+impl<AB: ExtensionBuilder> Air<AB> for ConstantAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0).expect("row_slice returned None");
+        let x = builder.lift(local[0]);
+        builder.assert_eq_ext(x, AB::EF::from_u32(self.value));
+    }
+}
+
+/// A linear base constraint next to a cubic extension constraint.
+pub struct CubicExtensionAir {}
+
+impl<F> BaseAir<F> for CubicExtensionAir {
+    fn width(&self) -> usize {
+        2
+    }
+}
+
+impl BaseAirWithPublicValues<Fr> for CubicExtensionAir {}
+
+impl<AB: ExtensionBuilder> Air<AB> for CubicExtensionAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0).expect("row_slice returned None");
+        let (a, b) = (local[0], local[1]);
+
+        builder.assert_eq(a, b);
+        let x = builder.lift(a);
+        builder.assert_eq_ext(x.clone() * x.clone() * x, builder.lift(b));
+    }
+}
+
+#[test]
+fn extension_constraints_count_towards_the_degree() {
+    assert_eq!(get_max_constraint_degree::<Fr, _>(&CubicExtensionAir {}), 3);
+    assert_eq!(get_log_quotient_degree::<Fr, _>(&CubicExtensionAir {}), 1);
+}
+
+#[test]
+fn coefficients_round_trip() {
+    let coeffs: Vec<Fr> = [3, 1, 4, 1].into_iter().map(Fr::from_u32).collect();
+    let x = EF::from_base_slice(&coeffs);
+    let round_trip: Vec<Fr> = x.as_base_slice();
+    assert_eq!(round_trip, coeffs);
+    assert_eq!(EF::from_base_slice(&round_trip), x);
+}
+
+#[test]
+fn coefficients_are_lowest_degree_first() {
+    let one = EF::from_base_slice(&[Fr::one(), Fr::zero(), Fr::zero(), Fr::zero()]);
+    assert_eq!(one, EF::one());
+
+    // The generator of the quartic extension satisfies X^4 = 11.
+    let x = EF::from_base_slice(&[Fr::zero(), Fr::one(), Fr::zero(), Fr::zero()]);
+    assert_eq!(x * x * x * x, EF::from_u32(11));
+}
+
+#[test]
+fn lifting_embeds_the_base_field() {
+    for value in [0, 1, 7, 1 << 30] {
+        assert_eq!(EF::from_base(Fr::from_u32(value)), EF::from_u32(value));
+    }
+    let (a, b) = (Fr::from_u32(12345), Fr::from_u32(678));
+    assert_eq!(EF::from_base(a) * EF::from_base(b), EF::from_base(a * b));
+    assert_eq!(EF::from_base(a) - EF::from_base(b), EF::from_base(a - b));
+}
+
+#[test]
+fn debug_builder_checks_extension_constraints() {
+    let air = ConstantAir { value: 7 };
+    let main = RowMajorMatrix::new(vec![Fr::from_u32(7); 4], 1);
+    assert!(check_constraints_with_extension::<_, EF, _>(&air, &main, &[]).is_ok());
+
+    let main = RowMajorMatrix::new([7, 8, 7, 7].into_iter().map(Fr::from_u32).collect(), 1);
+    let Err(ConstraintError::Constraint(error)) =
+        check_constraints_with_extension::<_, EF, _>(&air, &main, &[])
+    else {
+        panic!("row 1 is not 7");
+    };
+    assert_eq!(error.row, 1);
+    assert_eq!(
+        error.failure,
+        ConstraintFailure::NonZeroExtension(EF::from_u32(1))
+    );
+}
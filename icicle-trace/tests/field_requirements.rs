@@ -0,0 +1,107 @@
+use icicle_babybear::field::ScalarField as Fr;
+use icicle_core::bignum::BigNum;
+use icicle_trace::utils::{add2, ADD2_FIELD_REQUIREMENT};
+use icicle_trace::{
+    check_air_field_requirements, check_constraints, Air, AirBuilder, BaseAir,
+    BaseAirWithPublicValues, Characteristic, ConstraintError, FieldRequirement,
+};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+
+/// Checks `a = b + c mod 2^32` on 16-bit limbs, optionally also requiring a field below 2^30.
+pub struct Add2Air {
+    small_field_only: bool,
+}
+
+impl<F> BaseAir<F> for Add2Air {
+    fn width(&self) -> usize {
+        6
+    }
+
+    fn field_requirements(&self) -> Vec<FieldRequirement> {
+        if self.small_field_only {
+            vec![FieldRequirement::between("Add2Air", 1 << 17, 1 << 30)]
+        } else {
+            vec![]
+        }
+    }
+}
+
+impl BaseAirWithPublicValues<Fr> for Add2Air {}
+
+impl<AB: AirBuilder> Air<AB> for Add2Air {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0).expect("row_slice returned None");
+        let a = [local[0], local[1]];
+        let b = [local[2], local[3]];
+        let c = [local[4].into(), local[5].into()];
+        add2(builder, &a, &b, &c);
+    }
+}
+
+/// Uses a gadget which only works over fields above 2^40.
+pub struct WideGadgetAir {}
+
+impl<F> BaseAir<F> for WideGadgetAir {
+    fn width(&self) -> usize {
+        1
+    }
+}
+
+impl BaseAirWithPublicValues<Fr> for WideGadgetAir {}
+
+impl<AB: AirBuilder> Air<AB> for WideGadgetAir {
+    fn eval(&self, builder: &mut AB) {
+        builder.scope("wide", |builder| {
+            builder.require_field(FieldRequirement::greater_than("wide_gadget", 1 << 40));
+        });
+    }
+}
+
+#[test]
+fn babybear_characteristic() {
+    assert_eq!(
+        Characteristic::of::<Fr>().to_u64(),
+        Some(15 * (1 << 27) + 1)
+    );
+}
+
+#[test]
+fn gadget_requirements_are_collected_and_checked() {
+    let air = Add2Air {
+        small_field_only: false,
+    };
+    assert_eq!(check_air_field_requirements::<Fr, Add2Air>(&air), Ok(()));
+    assert_eq!(ADD2_FIELD_REQUIREMENT.check::<Fr>(), Ok(()));
+}
+
+#[test]
+fn violated_requirement_is_reported() {
+    let air = Add2Air {
+        small_field_only: true,
+    };
+    let err = check_air_field_requirements::<Fr, Add2Air>(&air).unwrap_err();
+    assert_eq!(err.requirement.source, "Add2Air");
+    assert_eq!(err.characteristic, Characteristic::of::<Fr>());
+}
+
+#[test]
+fn violated_gadget_requirement_is_reported_by_the_checker() {
+    let main = RowMajorMatrix::new(vec![Fr::zero(); 4], 1);
+    let result = check_constraints(&WideGadgetAir {}, &main, &[]);
+    let Err(ConstraintError::FieldRequirement {
+        error,
+        scope,
+        location: Some(location),
+    }) = result
+    else {
+        panic!("BabyBear is below 2^40");
+    };
+    assert_eq!(error.requirement.source, "wide_gadget");
+    assert_eq!(scope, "wide");
+    assert_eq!(location.file(), file!());
+
+    let err = check_air_field_requirements::<Fr, WideGadgetAir>(&WideGadgetAir {}).unwrap_err();
+    assert_eq!(err, error);
+}
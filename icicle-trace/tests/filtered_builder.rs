@@ -0,0 +1,47 @@
+use icicle_babybear::field::ScalarField as Fr;
+use icicle_trace::utils::add2;
+use icicle_trace::{
+    get_symbolic_constraints, Air, AirBuilder, BaseAir, BaseAirWithPublicValues, SymbolicExpression,
+};
+use p3_matrix::Matrix;
+
+/// Columns: `[selector_a, selector_b, bit, a_lo, a_hi, b_lo, b_hi]`.
+pub struct SelectedAir {}
+
+impl<F> BaseAir<F> for SelectedAir {
+    fn width(&self) -> usize {
+        7
+    }
+}
+
+impl BaseAirWithPublicValues<Fr> for SelectedAir {}
+
+impl<AB: AirBuilder> Air<AB> for SelectedAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local_option = main.row_slice(0);
+        let local = local_option.as_ref().expect("row_slice returned None");
+
+        builder.when(local[0]).when(local[1]).assert_bool(local[2]);
+
+        let c = [local[5].into(), local[6].into()];
+        add2(
+            &mut builder.when(local[0]),
+            &[local[3], local[4]],
+            &[local[5], local[6]],
+            &c,
+        );
+    }
+}
+
+#[test]
+fn nested_filters_multiply_conditions() {
+    let constraints = get_symbolic_constraints::<Fr, SelectedAir>(&SelectedAir {});
+    let degrees: Vec<usize> = constraints
+        .iter()
+        .map(SymbolicExpression::degree_multiple)
+        .collect();
+
+    // assert_bool (degree 2) under two selectors, then add2 (degree 2 each) under one.
+    assert_eq!(degrees, vec![4, 3, 3]);
+}
@@ -0,0 +1,117 @@
+use icicle_babybear::field::ScalarField as Fr;
+use icicle_core::bignum::BigNum;
+use icicle_trace::{
+    check_constraints, get_named_symbolic_constraints, Air, AirBuilder, BaseAir,
+    BaseAirWithPublicValues, ConstraintError, HorizontalAir, HorizontalGlue,
+};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+
+/// Counts up from zero, one step per row.
+pub struct CounterAir {}
+
+/// Holds `(x, x^2)` on each row.
+pub struct SquareAir {}
+
+impl BaseAir<Fr> for CounterAir {
+    fn width(&self) -> usize {
+        1
+    }
+}
+
+impl BaseAir<Fr> for SquareAir {
+    fn width(&self) -> usize {
+        2
+    }
+}
+
+impl BaseAirWithPublicValues<Fr> for CounterAir {}
+impl BaseAirWithPublicValues<Fr> for SquareAir {}
+
+impl<AB: AirBuilder<F = Fr>> Air<AB> for CounterAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0).expect("row_slice returned None")[0];
+        let next = main.row_slice(1).expect("row_slice returned None")[0];
+        builder.when_first_row().assert_zero(local);
+        builder.when_transition().assert_eq(next, local + Fr::one());
+    }
+}
+
+impl<AB: AirBuilder<F = Fr>> Air<AB> for SquareAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0).expect("row_slice returned None").to_vec();
+        builder.assert_eq(local[1], local[0] * local[0]);
+    }
+}
+
+/// Feeds the counter into the squaring AIR.
+pub struct CounterIsSquared;
+
+impl<AB: AirBuilder> HorizontalGlue<AB> for CounterIsSquared {
+    fn eval(
+        &self,
+        builder: &mut AB,
+        left: &RowMajorMatrix<AB::Var>,
+        right: &RowMajorMatrix<AB::Var>,
+    ) {
+        builder.assert_eq(left.values[0], right.values[0]);
+    }
+}
+
+type CounterSquareAir = HorizontalAir<CounterAir, SquareAir, CounterIsSquared>;
+
+fn air() -> CounterSquareAir {
+    HorizontalAir::with_glue(CounterAir {}, SquareAir {}, CounterIsSquared)
+}
+
+fn main_trace(air: &CounterSquareAir, squared: impl Fn(u32) -> u32) -> RowMajorMatrix<Fr> {
+    let counter = RowMajorMatrix::new((0..8).map(Fr::from_u32).collect(), 1);
+    let square = RowMajorMatrix::new(
+        (0..8)
+            .flat_map(|i| {
+                [
+                    Fr::from_u32(squared(i)),
+                    Fr::from_u32(squared(i) * squared(i)),
+                ]
+            })
+            .collect(),
+        2,
+    );
+    air.concat_traces(&counter, &square)
+}
+
+fn check(air: &CounterSquareAir, main: RowMajorMatrix<Fr>) -> Result<(), ConstraintError<Fr, Fr>> {
+    check_constraints(air, &main, &[])
+}
+
+#[test]
+fn side_by_side_airs_pass() {
+    let air = air();
+    let main = main_trace(&air, |i| i);
+    assert_eq!(main.width(), 3);
+    assert_eq!(
+        main.row_slice(5).unwrap().to_vec(),
+        [5, 5, 25].map(Fr::from_u32)
+    );
+    assert_eq!(check(&air, main), Ok(()));
+}
+
+#[test]
+fn glue_links_the_two_airs() {
+    let air = air();
+    let error = check(&air, main_trace(&air, |i| if i == 3 { 4 } else { i })).unwrap_err();
+    assert!(error
+        .to_string()
+        .starts_with("values didn't match on row 3"));
+}
+
+#[test]
+fn constraints_are_scoped_by_side() {
+    let names: Vec<_> = get_named_symbolic_constraints(&air())
+        .into_iter()
+        .map(|constraint| constraint.name)
+        .collect();
+    assert_eq!(names, ["left", "left", "right", "glue"]);
+}
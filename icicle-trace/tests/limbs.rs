@@ -0,0 +1,119 @@
+use core::array;
+
+use icicle_babybear::field::ScalarField as Fr;
+use icicle_core::bignum::BigNum;
+use icicle_trace::utils::{
+    limb_field_requirement, limbs_to_u64, pack_limbs, range_check_limbs, u32_to_limbs, u64_to_limbs,
+};
+use icicle_trace::{
+    check_constraints, get_max_constraint_degree, Air, AirBuilder, AirConfig, BaseAir,
+    BaseAirWithPublicValues, ConstraintError, SymbolicAirBuilder,
+};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+
+/// Column 0 holds a 32-bit value, columns 1..5 its 8-bit limbs and the rest the limbs' bits.
+pub struct ByteLimbAir {}
+
+const WIDTH: usize = 1 + 4 + 4 * 8;
+
+impl BaseAir<Fr> for ByteLimbAir {
+    fn width(&self) -> usize {
+        WIDTH
+    }
+}
+
+impl BaseAirWithPublicValues<Fr> for ByteLimbAir {}
+
+impl<AB: AirBuilder<F = Fr>> Air<AB> for ByteLimbAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0).expect("row_slice returned None").to_vec();
+        let limbs = &local[1..5];
+        let bits: [[AB::Var; 8]; 4] = array::from_fn(|i| array::from_fn(|j| local[5 + 8 * i + j]));
+
+        range_check_limbs::<AB, 8>(builder, limbs, &bits);
+        let packed = pack_limbs::<AB, 8, _>(builder, limbs.iter().copied());
+        builder.assert_eq(local[0], packed);
+    }
+}
+
+fn trace(values: &[u32]) -> RowMajorMatrix<Fr> {
+    let rows = values.iter().flat_map(|&value| {
+        let limbs: [Fr; 4] = u32_to_limbs::<_, 8, 4>(value);
+        let bits =
+            (0..4).flat_map(move |i| u64_to_limbs::<Fr, 1, 8>((value >> (8 * i)) as u64 & 0xFF));
+        [Fr::from_u32(value)].into_iter().chain(limbs).chain(bits)
+    });
+    RowMajorMatrix::new(rows.collect(), WIDTH)
+}
+
+fn check(main: RowMajorMatrix<Fr>) -> Result<(), ConstraintError<Fr, Fr>> {
+    check_constraints(&ByteLimbAir {}, &main, &[])
+}
+
+#[test]
+fn limbs_round_trip() {
+    let value = 0x0123_4567_89AB_CDEF;
+    let bytes: [Fr; 8] = u64_to_limbs::<_, 8, 8>(value);
+    assert_eq!(bytes[0], Fr::from_u32(0xEF));
+    assert_eq!(limbs_to_u64::<_, 8>(&bytes), value);
+
+    let halves: [Fr; 4] = u64_to_limbs::<_, 16, 4>(value);
+    assert_eq!(halves[3], Fr::from_u32(0x0123));
+    assert_eq!(limbs_to_u64::<_, 16>(&halves), value);
+
+    let truncated: [Fr; 2] = u64_to_limbs::<_, 16, 2>(value);
+    assert_eq!(limbs_to_u64::<_, 16>(&truncated), 0x89AB_CDEF);
+}
+
+#[test]
+fn zero_limbs_past_64_bits_are_allowed() {
+    let mut limbs = [Fr::zero(); 6];
+    limbs[..4].copy_from_slice(&u64_to_limbs::<Fr, 16, 4>(u64::MAX));
+    assert_eq!(limbs_to_u64::<_, 16>(&limbs), u64::MAX);
+    assert_eq!(limbs_to_u64::<_, 32>(&[Fr::zero(); 3]), 0);
+}
+
+#[test]
+#[should_panic(expected = "limbs do not fit in 64 bits")]
+fn nonzero_limb_past_64_bits_does_not_pack() {
+    let mut limbs = [Fr::zero(); 5];
+    limbs[4] = Fr::one();
+    limbs_to_u64::<_, 16>(&limbs);
+}
+
+#[test]
+#[should_panic(expected = "does not fit in 8 bits")]
+fn oversized_limb_does_not_pack() {
+    limbs_to_u64::<_, 8>(&[Fr::from_u32(0x100)]);
+}
+
+#[test]
+fn byte_limbs_are_range_checked() {
+    assert_eq!(
+        check(trace(&[0, 0xFFFF_FFFF, 0x1234_5678, 0x00FF_FF00])),
+        Ok(())
+    );
+
+    let air = ByteLimbAir {};
+    let mut builder = SymbolicAirBuilder::<Fr>::new(&AirConfig::from_air(&air));
+    air.eval(&mut builder);
+    assert_eq!(
+        builder.field_requirements(),
+        vec![limb_field_requirement::<8>()]
+    );
+    assert_eq!(get_max_constraint_degree(&air), 2);
+}
+
+#[test]
+fn limb_out_of_range_fails() {
+    let mut main = trace(&[1, 2, 3, 4]);
+    // Move 256 from the second limb into the first, which keeps the packed value.
+    main.values[WIDTH + 1] = Fr::from_u32(2 + 256);
+    main.values[WIDTH + 2] = Fr::zero() - Fr::one();
+    let error = check(main).unwrap_err();
+    assert!(error
+        .to_string()
+        .starts_with("values didn't match on row 1"));
+}
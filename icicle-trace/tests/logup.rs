@@ -0,0 +1,140 @@
+use icicle_babybear::field::{ExtensionField as EF, ScalarField as Fr};
+use icicle_core::bignum::BigNum;
+use icicle_trace::{
+    check_constraints_with_permutation, check_multi_phase_constraints, eval_logup,
+    generate_logup_trace, generate_multi_phase_trace, get_symbolic_extension_constraints,
+    logup_cumulative_sum, logup_permutation_width, Air, AirConfig, BaseAir,
+    BaseAirWithPublicValues, Entry, Interaction, InteractionAir, MultiPhaseAir, PairBuilder,
+    PermutationAirBuilder, SeededChallenger, SymbolicAirBuilder, SymbolicExpression,
+    SymbolicVariable, VirtualPairCol, LOGUP_NUM_CHALLENGES,
+};
+use p3_matrix::dense::RowMajorMatrix;
+
+/// Sends column 0 and receives column 1 on the same bus, so the two columns must be permutations
+/// of each other.
+pub struct ShuffleAir {}
+
+impl BaseAir<Fr> for ShuffleAir {
+    fn width(&self) -> usize {
+        2
+    }
+
+    fn permutation_width(&self) -> usize {
+        logup_permutation_width(self.interactions().len())
+    }
+
+    fn num_challenges(&self) -> usize {
+        LOGUP_NUM_CHALLENGES
+    }
+}
+
+impl BaseAirWithPublicValues<Fr> for ShuffleAir {}
+
+impl InteractionAir<Fr> for ShuffleAir {
+    fn interactions(&self) -> Vec<Interaction<Fr>> {
+        vec![
+            Interaction::send(
+                0,
+                vec![VirtualPairCol::single_main(0)],
+                VirtualPairCol::constant(Fr::one()),
+            ),
+            Interaction::receive(
+                0,
+                vec![VirtualPairCol::single_main(1)],
+                VirtualPairCol::constant(Fr::one()),
+            ),
+        ]
+    }
+}
+
+impl MultiPhaseAir<Fr, EF> for ShuffleAir {
+    fn generate_permutation_trace(
+        &self,
+        main: &RowMajorMatrix<Fr>,
+        challenges: &[EF],
+    ) -> RowMajorMatrix<EF> {
+        generate_logup_trace(&self.interactions(), None, main, challenges)
+    }
+}
+
+impl<AB> Air<AB> for ShuffleAir
+where
+    AB: PermutationAirBuilder<F = Fr, EF = EF> + PairBuilder,
+{
+    fn eval(&self, builder: &mut AB) {
+        eval_logup(builder, &self.interactions(), Some(EF::zero().into()));
+    }
+}
+
+fn trace(received: [u32; 4]) -> RowMajorMatrix<Fr> {
+    let sent = [3, 1, 4, 1];
+    let values = sent
+        .iter()
+        .zip(received.iter())
+        .flat_map(|(&s, &r)| [Fr::from_u32(s), Fr::from_u32(r)])
+        .collect();
+    RowMajorMatrix::new(values, 2)
+}
+
+#[test]
+fn permuted_columns_balance() {
+    let air = ShuffleAir {};
+    let trace = generate_multi_phase_trace(
+        &air,
+        trace([1, 4, 1, 3]),
+        &mut SeededChallenger::<Fr>::new(1),
+    );
+    assert_eq!(logup_cumulative_sum(&trace.permutation), EF::zero());
+    check_multi_phase_constraints(&air, &trace, &[]);
+}
+
+#[test]
+#[should_panic(expected = "extension constraints had nonzero value on row 3")]
+fn unbalanced_columns_fail() {
+    let air = ShuffleAir {};
+    let trace = generate_multi_phase_trace(
+        &air,
+        trace([1, 4, 1, 5]),
+        &mut SeededChallenger::<Fr>::new(1),
+    );
+    check_multi_phase_constraints(&air, &trace, &[]);
+}
+
+#[test]
+fn auxiliary_trace_is_checked_without_multi_phase_air() {
+    let air = ShuffleAir {};
+    let main = trace([1, 4, 1, 3]);
+    let challenges = [EF::from_u32(7), EF::from_u32(11)];
+    let permutation = generate_logup_trace(&air.interactions(), None, &main, &challenges);
+    assert_eq!(
+        check_constraints_with_permutation(&air, &main, &permutation, &challenges, &[]),
+        Ok(())
+    );
+
+    let error =
+        check_constraints_with_permutation(&air, &main, &permutation, &challenges[..1], &[])
+            .unwrap_err();
+    assert!(error
+        .to_string()
+        .starts_with("wrong number of challenges: expected 2, found 1"));
+}
+
+#[test]
+fn logup_constraints_have_degree_two() {
+    let constraints = get_symbolic_extension_constraints::<Fr, EF, ShuffleAir>(&ShuffleAir {});
+    assert_eq!(constraints.len(), 5);
+    assert!(constraints.iter().all(|c| c.degree_multiple() <= 2));
+}
+
+#[test]
+fn open_running_sum_is_returned() {
+    let air = ShuffleAir {};
+    let mut builder = SymbolicAirBuilder::<Fr, EF>::new(&AirConfig::from_air(&air));
+    let sum = eval_logup(&mut builder, &air.interactions(), None);
+    assert_eq!(
+        sum,
+        SymbolicExpression::from(SymbolicVariable::new(Entry::Permutation { offset: 0 }, 2))
+    );
+    // The last-row check on the final running sum is left to the caller.
+    assert_eq!(builder.extension_constraints().len(), 4);
+}
@@ -0,0 +1,104 @@
+use icicle_babybear::field::{ExtensionField as EF, ScalarField as Fr};
+use icicle_core::bignum::BigNum;
+use icicle_trace::{
+    check_multi_phase_constraints, generate_multi_phase_trace, try_check_multi_phase_constraints,
+    Air, BaseAir, BaseAirWithPublicValues, ConstraintError, ExtensionBuilder, ExtensionOf,
+    MultiPhaseAir, PermutationAirBuilder, SeededChallenger,
+};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+
+/// Accumulates `z[i] = prod_{j <= i} (gamma - x[j])` in a single auxiliary column.
+pub struct RunningProductAir {}
+
+impl<F> BaseAir<F> for RunningProductAir {
+    fn width(&self) -> usize {
+        1
+    }
+
+    fn permutation_width(&self) -> usize {
+        1
+    }
+
+    fn num_challenges(&self) -> usize {
+        1
+    }
+}
+
+impl BaseAirWithPublicValues<Fr> for RunningProductAir {}
+
+impl MultiPhaseAir<Fr, EF> for RunningProductAir {
+    fn generate_permutation_trace(
+        &self,
+        main: &RowMajorMatrix<Fr>,
+        challenges: &[EF],
+    ) -> RowMajorMatrix<EF> {
+        let gamma = challenges[0];
+        let mut acc = EF::one();
+        let values = main
+            .values
+            .iter()
+            .map(|x| {
+                acc = acc * (gamma - EF::from_base(*x));
+                acc
+            })
+            .collect();
+        RowMajorMatrix::new(values, 1)
+    }
+}
+
+impl<AB: PermutationAirBuilder> Air<AB> for RunningProductAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let x = main.row_slice(0).expect("row_slice returned None")[0];
+        let x_next = main.row_slice(1).expect("row_slice returned None")[0];
+
+        let permutation = builder.permutation();
+        let z = permutation.row_slice(0).expect("row_slice returned None")[0];
+        let z_next = permutation.row_slice(1).expect("row_slice returned None")[0];
+
+        let gamma: AB::ExprEF = builder.permutation_randomness()[0].into();
+        let first = gamma.clone() - builder.lift(x);
+        let step = z.into() * (gamma - builder.lift(x_next));
+
+        builder.when_first_row().assert_eq_ext(z, first);
+        builder.when_transition().assert_eq_ext(z_next, step);
+    }
+}
+
+fn main_trace() -> RowMajorMatrix<Fr> {
+    RowMajorMatrix::new((0..8).map(|i| Fr::from_u32(3 * i + 1)).collect(), 1)
+}
+
+#[test]
+fn running_product_passes() {
+    let air = RunningProductAir {};
+    let trace = generate_multi_phase_trace(&air, main_trace(), &mut SeededChallenger::<Fr>::new(7));
+    check_multi_phase_constraints(&air, &trace, &[]);
+}
+
+#[test]
+#[should_panic(expected = "extension constraints had nonzero value on row 4")]
+fn tampered_permutation_fails() {
+    let air = RunningProductAir {};
+    let mut trace =
+        generate_multi_phase_trace(&air, main_trace(), &mut SeededChallenger::<Fr>::new(7));
+    trace.permutation.values[5] = EF::one();
+    check_multi_phase_constraints(&air, &trace, &[]);
+}
+
+#[test]
+fn short_permutation_trace_is_reported() {
+    let air = RunningProductAir {};
+    let mut trace =
+        generate_multi_phase_trace(&air, main_trace(), &mut SeededChallenger::<Fr>::new(7));
+    trace.permutation = RowMajorMatrix::new(trace.permutation.values[..4].to_vec(), 1);
+    assert_eq!(
+        try_check_multi_phase_constraints(&air, &trace, &[]),
+        Err(ConstraintError::Shape {
+            mismatch: "permutation trace height does not match the main trace",
+            expected: 8,
+            found: 4,
+        })
+    );
+}
@@ -0,0 +1,100 @@
+use icicle_babybear::field::ScalarField as Fr;
+use icicle_core::bignum::BigNum;
+use icicle_trace::{
+    check_constraints, get_max_constraint_degree, Air, AirBuilder, BaseAir,
+    BaseAirWithPublicValues, ConstraintError, PaddedAir,
+};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+
+/// Counts from zero to four, one step per row, so only a trace of exactly five rows satisfies it.
+pub struct CounterAir {}
+
+impl BaseAir<Fr> for CounterAir {
+    fn width(&self) -> usize {
+        1
+    }
+}
+
+impl BaseAirWithPublicValues<Fr> for CounterAir {}
+
+impl<AB: AirBuilder<F = Fr>> Air<AB> for CounterAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0).expect("row_slice returned None")[0];
+        let next = main.row_slice(1).expect("row_slice returned None")[0];
+
+        builder.when_first_row().assert_zero(local);
+        builder.when_transition().assert_eq(next, local + Fr::one());
+        let four = builder.from_u32(4);
+        builder.when_last_row().assert_eq(local, four);
+    }
+}
+
+fn counter(n: u32) -> RowMajorMatrix<Fr> {
+    RowMajorMatrix::new((0..n).map(Fr::from_u32).collect(), 1)
+}
+
+fn check(
+    air: &PaddedAir<CounterAir>,
+    main: RowMajorMatrix<Fr>,
+) -> Result<(), ConstraintError<Fr, Fr>> {
+    check_constraints(air, &main, &[])
+}
+
+#[test]
+fn padding_rows_are_unconstrained() {
+    let air = PaddedAir::new(CounterAir {});
+    let padded = air.pad_trace(counter(5));
+    assert_eq!(padded.height(), 8);
+    assert_eq!(padded.width(), 2);
+    assert_eq!(air.unpad_trace(&padded), counter(5));
+    assert_eq!(check(&air, padded), Ok(()));
+}
+
+#[test]
+fn last_real_row_is_constrained() {
+    let air = PaddedAir::new(CounterAir {});
+    let padded = air.pad_trace(counter(4));
+    assert_eq!(padded.height(), 4);
+    let error = check(&air, padded).unwrap_err();
+    assert!(error
+        .to_string()
+        .starts_with("constraints had nonzero value on row 3"));
+}
+
+#[test]
+fn real_row_after_padding_fails() {
+    let air = PaddedAir::new(CounterAir {});
+    let mut main = air.pad_trace(counter(5));
+    main.values[6 * 2 + 1] = Fr::one();
+    let error = check(&air, main).unwrap_err();
+    assert!(error
+        .to_string()
+        .starts_with("constraints had nonzero value on row 5"));
+}
+
+#[test]
+fn all_padding_trace_fails() {
+    let air = PaddedAir::new(CounterAir {});
+    // Without the first-row check, zeroing `is_real` would switch off every constraint.
+    let mut main = air.pad_trace(counter(5));
+    for r in 0..main.height() {
+        main.values[r * 2 + 1] = Fr::zero();
+    }
+    let error = check(&air, main).unwrap_err();
+    assert!(error
+        .to_string()
+        .starts_with("constraints had nonzero value on row 0"));
+
+    let error = check(&air, air.pad_trace(counter(0))).unwrap_err();
+    assert!(error
+        .to_string()
+        .starts_with("constraints had nonzero value on row 0"));
+}
+
+#[test]
+fn gating_raises_degree() {
+    assert_eq!(get_max_constraint_degree(&CounterAir {}), 2);
+    assert_eq!(get_max_constraint_degree(&PaddedAir::new(CounterAir {})), 4);
+}
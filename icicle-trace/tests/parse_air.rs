@@ -0,0 +1,125 @@
+use icicle_babybear::field::ScalarField as Fr;
+use icicle_core::bignum::BigNum;
+use icicle_trace::{
+    check_constraints, get_max_constraint_degree, get_symbolic_constraints, parse_air, BaseAir,
+    BaseAirWithPublicValues, ParseError, SymbolicExpression,
+};
+use p3_matrix::dense::RowMajorMatrix;
+
+const FIBONACCI: &str = "
+    # From public values a, b to x.
+    col left, right;
+    first: left = pub[0];
+    first: right = pub[1];
+    transition: left' = right;
+    transition: right' = left + right;
+    last: right = pub[2];
+";
+
+fn parse_error(source: &str) -> ParseError {
+    parse_air::<Fr>(source).expect_err("program should not parse")
+}
+
+#[test]
+fn parsed_fibonacci_passes() {
+    let air = parse_air::<Fr>(FIBONACCI).unwrap();
+    assert_eq!(air.column_names(), ["left", "right"]);
+    assert_eq!(air.num_public_values(), 3);
+    assert_eq!(get_symbolic_constraints(&air).len(), 5);
+
+    let mut values = vec![0u32, 1];
+    for r in 1..8 {
+        values.extend([values[2 * r - 1], values[2 * r - 2] + values[2 * r - 1]]);
+    }
+    let main = RowMajorMatrix::new(values.into_iter().map(Fr::from_u32).collect(), 2);
+    let public_values = [0, 1, 21].map(Fr::from_u32);
+    assert_eq!(check_constraints(&air, &main, &public_values), Ok(()));
+}
+
+#[test]
+fn precedence_and_degrees() {
+    let air = parse_air::<Fr>("col x, y; y = -x^3 + 2 * (x - 1)").unwrap();
+    let (x, y) = (air.local("x"), air.local("y"));
+    let x: SymbolicExpression<Fr> = x.into();
+    let expected = y
+        - (-(x.clone() * x.clone() * x.clone())
+            + SymbolicExpression::from_u32(2) * (x - SymbolicExpression::one()));
+    assert_eq!(air.constraints(), [expected]);
+    assert_eq!(get_max_constraint_degree(&air), 3);
+}
+
+#[test]
+fn primes_widen_the_window() {
+    let air = parse_air::<Fr>("col x; transition: x'' = x' + x").unwrap();
+    assert_eq!(air.window_size(), 3);
+    assert!(matches!(
+        air.constraints()[0],
+        SymbolicExpression::Mul { ref x, .. } if **x == SymbolicExpression::IsTransitionWindow(3)
+    ));
+}
+
+#[test]
+fn errors_point_at_the_offending_token() {
+    let error = parse_error("col a;\nfirst: b = 1");
+    assert_eq!((error.line, error.column), (2, 8));
+    assert_eq!(error.to_string(), "line 2, column 8: unknown column `b`");
+
+    assert_eq!(parse_error("col a; a = (a + 1").message, "expected `)`");
+    assert_eq!(
+        parse_error("col a, a").message,
+        "column `a` is declared twice"
+    );
+    assert_eq!(
+        parse_error("col a; a = a % 2").message,
+        "unexpected character `%`"
+    );
+    assert_eq!(
+        parse_error("col a; a a").message,
+        "expected `;` between statements"
+    );
+    assert_eq!(
+        parse_error("col a; every: a = 0").message,
+        "unknown selector `every`, expected `first`, `last` or `transition`"
+    );
+    assert_eq!(
+        parse_error("col a; a = ").message,
+        "unexpected end of input"
+    );
+
+    let error = parse_error("col a; a = a^65");
+    assert_eq!((error.line, error.column), (1, 14));
+    assert_eq!(error.message, "exponents must be at most 64");
+    assert!(parse_air::<Fr>("col a; a = a^64").is_ok());
+}
+
+#[test]
+fn nesting_is_limited() {
+    let nested = |open: &str, close: &str, depth| {
+        format!("col a; a = {}a{}", open.repeat(depth), close.repeat(depth))
+    };
+    assert!(parse_air::<Fr>(&nested("(", ")", 64)).is_ok());
+    assert!(parse_air::<Fr>(&nested("-", "", 64)).is_ok());
+
+    let message = "parentheses and negations may nest at most 64 deep";
+    assert_eq!(parse_error(&nested("(", ")", 65)).message, message);
+    assert_eq!(parse_error(&nested("-", "", 65)).message, message);
+    assert_eq!(parse_error(&nested("(-", ")", 100_000)).message, message);
+}
+
+#[test]
+fn expanded_size_is_limited() {
+    assert!(parse_air::<Fr>("col a; a = (a^32)^32").is_ok());
+
+    let message = "constraints may have at most 4096 nodes once powers are expanded";
+    let error = parse_error("col a; a = (a^64)^64");
+    assert_eq!((error.line, error.column), (1, 19));
+    assert_eq!(error.message, message);
+    assert_eq!(parse_error("col a; a = ((a^64)^64)^64").message, message);
+    assert_eq!(
+        parse_error(&format!("col a; a = {}", ["a"; 5000].join(" + "))).message,
+        message
+    );
+
+    // The limit is per constraint.
+    assert!(parse_air::<Fr>("col a; a = (a^32)^32; a = (a^32)^32").is_ok());
+}
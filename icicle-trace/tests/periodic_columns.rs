@@ -0,0 +1,55 @@
+use icicle_babybear::field::ScalarField as Fr;
+use icicle_core::bignum::BigNum;
+use icicle_trace::{
+    get_max_constraint_degree, get_symbolic_constraints, Air, AirBuilder, BaseAir,
+    BaseAirWithPublicValues, Entry, PeriodicAirBuilder, SymbolicExpression,
+};
+use p3_matrix::Matrix;
+
+/// A single accumulator column which adds the square of a round constant repeating every four rows.
+pub struct RoundConstantAir {}
+
+impl<F: BigNum> BaseAir<F> for RoundConstantAir {
+    fn width(&self) -> usize {
+        1
+    }
+
+    fn periodic_columns(&self) -> Vec<Vec<F>> {
+        vec![(1..=4).map(F::from_u32).collect()]
+    }
+}
+
+impl BaseAirWithPublicValues<Fr> for RoundConstantAir {}
+
+impl<AB: PeriodicAirBuilder> Air<AB> for RoundConstantAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0).expect("row_slice returned None")[0];
+        let next = main.row_slice(1).expect("row_slice returned None")[0];
+        let round_constant = builder.periodic_values()[0];
+
+        builder
+            .when_transition()
+            .assert_eq(next, local + round_constant * round_constant);
+    }
+}
+
+fn reads_periodic(expr: &SymbolicExpression<Fr>) -> bool {
+    match expr {
+        SymbolicExpression::Variable(v) => v.entry == Entry::Periodic,
+        SymbolicExpression::Add { x, y, .. }
+        | SymbolicExpression::Sub { x, y, .. }
+        | SymbolicExpression::Mul { x, y, .. } => reads_periodic(x) || reads_periodic(y),
+        SymbolicExpression::Neg { x, .. } => reads_periodic(x),
+        _ => false,
+    }
+}
+
+#[test]
+fn periodic_column_is_a_degree_one_variable() {
+    let air = RoundConstantAir {};
+    let constraints = get_symbolic_constraints::<Fr, RoundConstantAir>(&air);
+    assert_eq!(constraints.len(), 1);
+    assert!(reads_periodic(&constraints[0]));
+    assert_eq!(get_max_constraint_degree::<Fr, RoundConstantAir>(&air), 2);
+}
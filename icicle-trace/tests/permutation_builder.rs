@@ -0,0 +1,115 @@
+use icicle_babybear::field::{ExtensionField as EF, ScalarField as Fr};
+use icicle_core::bignum::BigNum;
+use icicle_trace::{
+    check_multi_phase_constraints, generate_multi_phase_trace, get_symbolic_extension_constraints,
+    Air, AirConfig, BaseAir, BaseAirWithPublicValues, Entry, ExtensionOf, FixedChallenges,
+    MultiPhaseAir, PermutationAirBuilder, SymbolicAirBuilder, SymbolicVariable,
+};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+
+/// Holds `x * r[i % 2]` in auxiliary column `i`, for three auxiliary columns and two challenges.
+pub struct ScaledCopiesAir {}
+
+impl<F> BaseAir<F> for ScaledCopiesAir {
+    fn width(&self) -> usize {
+        1
+    }
+
+    fn permutation_width(&self) -> usize {
+        3
+    }
+
+    fn num_challenges(&self) -> usize {
+        2
+    }
+}
+
+impl BaseAirWithPublicValues<Fr> for ScaledCopiesAir {}
+
+impl MultiPhaseAir<Fr, EF> for ScaledCopiesAir {
+    fn generate_permutation_trace(
+        &self,
+        main: &RowMajorMatrix<Fr>,
+        challenges: &[EF],
+    ) -> RowMajorMatrix<EF> {
+        let values = main
+            .values
+            .iter()
+            .flat_map(|x| (0..3).map(move |i| EF::from_base(*x) * challenges[i % 2]))
+            .collect();
+        RowMajorMatrix::new(values, 3)
+    }
+}
+
+impl<AB: PermutationAirBuilder> Air<AB> for ScaledCopiesAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let x = main.row_slice(0).expect("row_slice returned None")[0];
+        let permutation = builder.permutation();
+        let local = permutation.row_slice(0).expect("row_slice returned None");
+        let randomness = builder.permutation_randomness().to_vec();
+
+        for (i, &copy) in local.iter().enumerate() {
+            let scaled = builder.lift(x) * randomness[i % 2].into();
+            builder.assert_eq_ext(copy, scaled);
+        }
+    }
+}
+
+fn main_trace() -> RowMajorMatrix<Fr> {
+    RowMajorMatrix::new((0..4).map(|i| Fr::from_u32(i + 2)).collect(), 1)
+}
+
+fn challenges() -> FixedChallenges<EF> {
+    FixedChallenges::new(vec![EF::from_u32(5), EF::from_u32(9)])
+}
+
+#[test]
+fn symbolic_builder_exposes_the_permutation_trace() {
+    let builder = SymbolicAirBuilder::<Fr, EF>::new(&AirConfig::from_air(&ScaledCopiesAir {}));
+
+    let permutation = builder.permutation();
+    assert_eq!((permutation.width(), permutation.height()), (3, 2));
+    for offset in 0..2 {
+        let row = permutation
+            .row_slice(offset)
+            .expect("row_slice returned None");
+        for (index, variable) in row.iter().enumerate() {
+            assert_eq!(
+                *variable,
+                SymbolicVariable::new(Entry::Permutation { offset }, index)
+            );
+        }
+    }
+
+    let randomness = builder.permutation_randomness();
+    assert_eq!(
+        randomness,
+        [
+            SymbolicVariable::new(Entry::Challenge, 0),
+            SymbolicVariable::new(Entry::Challenge, 1),
+        ]
+    );
+
+    let constraints = get_symbolic_extension_constraints::<Fr, EF, _>(&ScaledCopiesAir {});
+    assert_eq!(constraints.len(), 3);
+}
+
+#[test]
+fn valid_permutation_trace_passes() {
+    let air = ScaledCopiesAir {};
+    let trace = generate_multi_phase_trace(&air, main_trace(), &mut challenges());
+    assert_eq!(trace.challenges, [EF::from_u32(5), EF::from_u32(9)]);
+    check_multi_phase_constraints(&air, &trace, &[]);
+}
+
+#[test]
+#[should_panic(expected = "extension constraints had nonzero value on row 2")]
+fn bad_permutation_trace_fails() {
+    let air = ScaledCopiesAir {};
+    let mut trace = generate_multi_phase_trace(&air, main_trace(), &mut challenges());
+    // Column 2 of row 2 should be scaled by the first challenge, not the second.
+    trace.permutation.values[2 * 3 + 2] = EF::from_u32(4 * 9);
+    check_multi_phase_constraints(&air, &trace, &[]);
+}
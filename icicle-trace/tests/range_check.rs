@@ -0,0 +1,198 @@
+use icicle_babybear::field::{ExtensionField as EF, ScalarField as Fr};
+use icicle_core::bignum::BigNum;
+use icicle_trace::{
+    check_air_field_requirements, check_bus_balance, check_multi_phase_constraints,
+    generate_logup_trace, generate_multi_phase_trace, logup_cumulative_sum,
+    logup_permutation_width, Air, BaseAir, BaseAirWithPublicValues, BusInstance, Interaction,
+    InteractionAir, MultiPhaseAir, PairBuilder, PermutationAirBuilder, RangeCheckAir,
+    SeededChallenger, VirtualPairCol, LOGUP_NUM_CHALLENGES,
+};
+use p3_matrix::dense::RowMajorMatrix;
+
+const TABLE: RangeCheckAir = RangeCheckAir::new(8, 0);
+
+/// Shifting a one-bit lookup into a 16-bit table needs a field larger than `2^31`.
+const WIDE_TABLE: RangeCheckAir = RangeCheckAir::new(16, 1);
+
+/// The bus `LimbAir` shuffles on, next to its range checks.
+const SHUFFLE_BUS: usize = 2;
+
+/// Range-checks column 0 to 8 bits and column 1 to 4 bits, and sends column 2 and receives column
+/// 3 on another bus, so the two must be permutations of each other.
+pub struct LimbAir {}
+
+impl LimbAir {
+    fn range_checks() -> [(VirtualPairCol<Fr>, usize); 2] {
+        [
+            (VirtualPairCol::single_main(0), 8),
+            (VirtualPairCol::single_main(1), 4),
+        ]
+    }
+}
+
+impl BaseAir<Fr> for LimbAir {
+    fn width(&self) -> usize {
+        4
+    }
+
+    fn permutation_width(&self) -> usize {
+        logup_permutation_width(self.interactions().len())
+    }
+
+    fn num_challenges(&self) -> usize {
+        LOGUP_NUM_CHALLENGES
+    }
+}
+
+impl BaseAirWithPublicValues<Fr> for LimbAir {}
+
+impl InteractionAir<Fr> for LimbAir {
+    fn interactions(&self) -> Vec<Interaction<Fr>> {
+        let one = VirtualPairCol::constant(Fr::one());
+        let mut interactions = vec![
+            Interaction::send(
+                SHUFFLE_BUS,
+                vec![VirtualPairCol::single_main(2)],
+                one.clone(),
+            ),
+            Interaction::receive(SHUFFLE_BUS, vec![VirtualPairCol::single_main(3)], one),
+        ];
+        interactions.extend(TABLE.range_checks(&Self::range_checks()));
+        interactions
+    }
+}
+
+impl MultiPhaseAir<Fr, EF> for LimbAir {
+    fn generate_permutation_trace(
+        &self,
+        main: &RowMajorMatrix<Fr>,
+        challenges: &[EF],
+    ) -> RowMajorMatrix<EF> {
+        generate_logup_trace(&self.interactions(), None, main, challenges)
+    }
+}
+
+impl<AB> Air<AB> for LimbAir
+where
+    AB: PermutationAirBuilder<F = Fr> + PairBuilder,
+{
+    fn eval(&self, builder: &mut AB) {
+        TABLE.eval_range_checks(builder, &self.interactions(), &Self::range_checks());
+    }
+}
+
+/// Range-checks column 0 to a single bit against `WIDE_TABLE`.
+pub struct BitAir {}
+
+impl BaseAir<Fr> for BitAir {
+    fn width(&self) -> usize {
+        1
+    }
+
+    fn permutation_width(&self) -> usize {
+        logup_permutation_width(self.interactions().len())
+    }
+
+    fn num_challenges(&self) -> usize {
+        LOGUP_NUM_CHALLENGES
+    }
+}
+
+impl BaseAirWithPublicValues<Fr> for BitAir {}
+
+impl InteractionAir<Fr> for BitAir {
+    fn interactions(&self) -> Vec<Interaction<Fr>> {
+        WIDE_TABLE.range_checks(&[(VirtualPairCol::single_main(0), 1)])
+    }
+}
+
+impl<AB> Air<AB> for BitAir
+where
+    AB: PermutationAirBuilder<F = Fr> + PairBuilder,
+{
+    fn eval(&self, builder: &mut AB) {
+        let checks = [(VirtualPairCol::single_main(0), 1)];
+        WIDE_TABLE.eval_range_checks(builder, &self.interactions(), &checks);
+    }
+}
+
+/// The limbs of each row, next to a column and its reverse for the shuffle.
+fn limbs(rows: [(u32, u32); 4]) -> RowMajorMatrix<Fr> {
+    let values = (0..4)
+        .flat_map(|r| {
+            let (a, b) = rows[r];
+            [a, b, 100 + r as u32, 103 - r as u32].map(Fr::from_u32)
+        })
+        .collect();
+    RowMajorMatrix::new(values, 4)
+}
+
+#[test]
+fn lookups_balance_against_the_table() {
+    let air = LimbAir {};
+    let main = limbs([(255, 15), (0, 0), (17, 3), (17, 15)]);
+    assert_eq!(air.interactions().len(), 5);
+
+    let mut multiplicities = TABLE.multiplicities();
+    multiplicities.add_trace(&air.interactions(), None, &main);
+    let table_main = multiplicities.into_trace::<Fr>();
+
+    let table_preprocessed = BaseAir::<Fr>::preprocessed_trace(&TABLE).unwrap();
+    let instances = [
+        BusInstance::new(&air, &main, &[]),
+        BusInstance::new(&TABLE, &table_main, &[]),
+    ];
+    check_bus_balance(&instances).unwrap();
+    assert_eq!(table_preprocessed.values[17], Fr::from_u32(17));
+
+    let trace = generate_multi_phase_trace(&air, main, &mut SeededChallenger::<Fr>::new(5));
+    let table_trace =
+        generate_multi_phase_trace(&TABLE, table_main, &mut SeededChallenger::<Fr>::new(5));
+    check_multi_phase_constraints(&air, &trace, &[]);
+    check_multi_phase_constraints(&TABLE, &table_trace, &[]);
+    assert_eq!(
+        logup_cumulative_sum(&trace.permutation) + logup_cumulative_sum(&table_trace.permutation),
+        EF::zero()
+    );
+}
+
+#[test]
+#[should_panic(expected = "is out of range of the range-check table")]
+fn oversized_limb_is_rejected() {
+    let air = LimbAir {};
+    let main = limbs([(255, 15), (0, 0), (17, 20), (17, 15)]);
+    let mut multiplicities = TABLE.multiplicities();
+    multiplicities.add_trace(&air.interactions(), None, &main);
+}
+
+#[test]
+fn shifted_lookup_requires_a_large_enough_field() {
+    check_air_field_requirements::<Fr, _>(&LimbAir {}).unwrap();
+
+    let error = check_air_field_requirements::<Fr, _>(&BitAir {}).unwrap_err();
+    assert_eq!(
+        Some(error.requirement),
+        WIDE_TABLE.range_check_requirement(1)
+    );
+    assert_eq!(TABLE.range_check_requirement(8), None);
+}
+
+#[test]
+fn broken_shuffle_next_to_the_lookups_fails() {
+    let air = LimbAir {};
+    let mut main = limbs([(255, 15), (0, 0), (17, 3), (17, 15)]);
+    main.values[3] = Fr::from_u32(42);
+
+    let mut multiplicities = TABLE.multiplicities();
+    multiplicities.add_trace(&air.interactions(), None, &main);
+    let table_main = multiplicities.into_trace::<Fr>();
+    let instances = [
+        BusInstance::new(&air, &main, &[]),
+        BusInstance::new(&TABLE, &table_main, &[]),
+    ];
+    let error = check_bus_balance(&instances).unwrap_err();
+    assert!(error
+        .imbalances
+        .iter()
+        .all(|imbalance| imbalance.bus == SHUFFLE_BUS));
+}
@@ -0,0 +1,99 @@
+use icicle_babybear::field::ScalarField as Fr;
+use icicle_core::bignum::BigNum;
+use icicle_trace::{
+    check_constraints, get_max_constraint_degree, get_symbolic_constraints, Air, AirBuilder,
+    BaseAir, BaseAirWithPublicValues, ConstraintError, SymbolicExpression,
+};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+
+/// A counter which restarts at zero every eight rows and must equal 5 on row 13.
+pub struct CycleCounterAir {}
+
+impl<F> BaseAir<F> for CycleCounterAir {
+    fn width(&self) -> usize {
+        1
+    }
+}
+
+impl BaseAirWithPublicValues<Fr> for CycleCounterAir {}
+
+impl<AB: AirBuilder> Air<AB> for CycleCounterAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0).expect("row_slice returned None")[0];
+        let next = main.row_slice(1).expect("row_slice returned None")[0];
+
+        let one = builder.from_u32(1);
+        let five = builder.from_u32(5);
+        let not_segment_end = builder.one() - builder.is_row_mod(7, 8);
+
+        builder.when_segment_start(8).assert_zero(local);
+        builder
+            .when_transition()
+            .when(not_segment_end)
+            .assert_eq(next, local + one);
+        builder.when_row(13).assert_eq(local, five);
+    }
+}
+
+#[test]
+fn selectors_are_symbolic_and_degree_one() {
+    let air = CycleCounterAir {};
+    let constraints = get_symbolic_constraints::<Fr, CycleCounterAir>(&air);
+    assert_eq!(constraints.len(), 3);
+
+    match &constraints[0] {
+        SymbolicExpression::Mul { x, .. } => assert_eq!(
+            **x,
+            SymbolicExpression::IsPeriodicRow {
+                residue: 0,
+                period: 8
+            }
+        ),
+        other => panic!("unexpected constraint {:?}", other),
+    }
+    match &constraints[2] {
+        SymbolicExpression::Mul { x, .. } => assert_eq!(**x, SymbolicExpression::IsRow(13)),
+        other => panic!("unexpected constraint {:?}", other),
+    }
+
+    assert_eq!(get_max_constraint_degree::<Fr, CycleCounterAir>(&air), 2);
+}
+
+fn counter_trace(height: u32) -> RowMajorMatrix<Fr> {
+    RowMajorMatrix::new((0..height).map(|i| Fr::from_u32(i % 8)).collect(), 1)
+}
+
+#[test]
+fn selectors_pick_out_their_rows() {
+    let air = CycleCounterAir {};
+    assert!(check_constraints(&air, &counter_trace(16), &[]).is_ok());
+
+    let mut main = counter_trace(16);
+    main.values[13] = Fr::from_u32(6);
+    main.values[14] = Fr::from_u32(7);
+    main.values[15] = Fr::from_u32(8);
+    let Err(ConstraintError::Constraint(error)) = check_constraints(&air, &main, &[]) else {
+        panic!("row 13 is not 5");
+    };
+    assert_eq!((error.row, error.constraint_index), (12, 1));
+}
+
+#[test]
+fn row_selector_beyond_the_trace_is_reported() {
+    let error = check_constraints(&CycleCounterAir {}, &counter_trace(8), &[]);
+    let Err(ConstraintError::Selector { row, selector, .. }) = error else {
+        panic!("unexpected result {:?}", error);
+    };
+    assert_eq!((row, selector.as_str()), (0, "is_row(13)"));
+}
+
+#[test]
+fn periodic_selector_longer_than_the_trace_is_reported() {
+    let error = check_constraints(&CycleCounterAir {}, &counter_trace(4), &[]);
+    let Err(ConstraintError::Selector { row, selector, .. }) = error else {
+        panic!("unexpected result {:?}", error);
+    };
+    assert_eq!((row, selector.as_str()), (0, "is_row_mod(0, 8)"));
+}
@@ -0,0 +1,62 @@
+use icicle_trace::columns::column_map;
+use icicle_trace::{AirColumns, TraceView, TraceViewMut};
+use p3_matrix::dense::RowMajorMatrix;
+use rayon::prelude::*;
+
+#[derive(Debug, AirColumns)]
+#[repr(C)]
+pub struct StepCols<T> {
+    pub step: T,
+    pub acc: [T; 2],
+}
+
+const NUM_STEP_COLS: usize = <StepCols<u8> as AirColumns<u8>>::NUM_COLUMNS;
+const STEP_COL_MAP: StepCols<usize> = column_map();
+
+fn zero_trace(height: usize) -> RowMajorMatrix<u64> {
+    RowMajorMatrix::new(vec![0; height * NUM_STEP_COLS], NUM_STEP_COLS)
+}
+
+#[test]
+fn rows_are_indexed_by_field() {
+    let mut trace = zero_trace(4);
+    let mut rows = TraceViewMut::<StepCols<u64>>::new(&mut trace);
+    assert_eq!(rows.height(), 4);
+    rows[2].acc[1] = 7;
+    rows.iter_mut()
+        .enumerate()
+        .for_each(|(i, row)| row.step = i as u64);
+
+    assert_eq!(trace.values[2 * NUM_STEP_COLS + STEP_COL_MAP.acc[1]], 7);
+    let rows = TraceView::<StepCols<u64>>::new(&trace);
+    assert_eq!(rows[3].step, 3);
+    assert_eq!(rows.iter().map(|row| row.step).sum::<u64>(), 6);
+}
+
+#[test]
+fn parallel_chunks_cover_every_row() {
+    let mut trace = zero_trace(10);
+    let mut rows = TraceViewMut::<StepCols<u64>>::new(&mut trace);
+    rows.par_chunks_mut(4)
+        .enumerate()
+        .for_each(|(block, chunk)| {
+            for (i, row) in chunk.iter_mut().enumerate() {
+                row.step = i as u64;
+                row.acc = [block as u64; 2];
+            }
+        });
+
+    let rows = TraceView::<StepCols<u64>>::new(&trace);
+    let chunk_lens: Vec<_> = rows.chunks(4).map(<[_]>::len).collect();
+    assert_eq!(chunk_lens, [4, 4, 2]);
+    assert_eq!(rows[9].step, 1);
+    assert_eq!(rows[9].acc, [2, 2]);
+    assert_eq!(rows.par_iter().map(|row| row.acc[0]).sum::<u64>(), 8);
+}
+
+#[test]
+#[should_panic(expected = "trace width does not match the column struct")]
+fn mismatched_width_is_rejected() {
+    let trace = RowMajorMatrix::new(vec![0u64; 8], 2);
+    TraceView::<StepCols<u64>>::new(&trace);
+}
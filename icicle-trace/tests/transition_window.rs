@@ -0,0 +1,62 @@
+use icicle_babybear::field::ScalarField as Fr;
+use icicle_trace::{
+    get_symbolic_constraints, Air, AirBuilder, BaseAir, BaseAirWithPublicValues, Entry,
+    SymbolicExpression,
+};
+use p3_matrix::Matrix;
+
+/// A single column satisfying `x[i + 2] = x[i + 1] + x[i]`.
+pub struct RecurrenceAir {}
+
+impl<F> BaseAir<F> for RecurrenceAir {
+    fn width(&self) -> usize {
+        1
+    }
+
+    fn window_size(&self) -> usize {
+        3
+    }
+}
+
+impl BaseAirWithPublicValues<Fr> for RecurrenceAir {}
+
+impl<AB: AirBuilder> Air<AB> for RecurrenceAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let rows: Vec<AB::Var> = (0..3)
+            .map(|offset| main.row_slice(offset).expect("row_slice returned None")[0])
+            .collect();
+
+        builder
+            .when_transition_window(3)
+            .assert_eq(rows[2], rows[1] + rows[0]);
+    }
+}
+
+fn max_main_offset(expr: &SymbolicExpression<Fr>) -> usize {
+    match expr {
+        SymbolicExpression::Variable(v) => match v.entry {
+            Entry::Main { offset } => offset,
+            _ => 0,
+        },
+        SymbolicExpression::Add { x, y, .. }
+        | SymbolicExpression::Sub { x, y, .. }
+        | SymbolicExpression::Mul { x, y, .. } => max_main_offset(x).max(max_main_offset(y)),
+        SymbolicExpression::Neg { x, .. } => max_main_offset(x),
+        _ => 0,
+    }
+}
+
+#[test]
+fn window_of_three_rows() {
+    let constraints = get_symbolic_constraints::<Fr, RecurrenceAir>(&RecurrenceAir {});
+    assert_eq!(constraints.len(), 1);
+    assert_eq!(max_main_offset(&constraints[0]), 2);
+
+    match &constraints[0] {
+        SymbolicExpression::Mul { x, .. } => {
+            assert_eq!(**x, SymbolicExpression::IsTransitionWindow(3))
+        }
+        other => panic!("unexpected constraint {:?}", other),
+    }
+}
@@ -0,0 +1,320 @@
+use icicle_babybear::field::{ExtensionField as EF, ScalarField as Fr};
+use icicle_core::bignum::BigNum;
+use icicle_trace::{
+    check_constraints, eval_logup, generate_logup_trace, generate_multi_phase_trace,
+    get_named_symbolic_constraints, get_symbolic_constraints, logup_permutation_width,
+    try_check_multi_phase_constraints, Air, AirBuilder, BaseAir, BaseAirWithPublicValues,
+    ConstraintError, DebugConstraintBuilder, Interaction, InteractionAir, MultiPhaseAir,
+    PairBuilder, PeriodicAirBuilder, PermutationAirBuilder, SeededChallenger, VerticalAir,
+    VirtualPairCol, LOGUP_NUM_CHALLENGES,
+};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+
+/// Holds `(x, x^2)` on each row.
+pub struct SquareAir {}
+
+/// Counts up from zero, one step per row.
+pub struct CounterAir {}
+
+impl BaseAir<Fr> for SquareAir {
+    fn width(&self) -> usize {
+        2
+    }
+}
+
+impl BaseAir<Fr> for CounterAir {
+    fn width(&self) -> usize {
+        1
+    }
+}
+
+impl BaseAirWithPublicValues<Fr> for SquareAir {}
+impl BaseAirWithPublicValues<Fr> for CounterAir {}
+
+impl<AB: AirBuilder<F = Fr>> Air<AB> for SquareAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0).expect("row_slice returned None").to_vec();
+        builder.assert_eq(local[1], local[0] * local[0]);
+    }
+}
+
+impl<AB: AirBuilder<F = Fr>> Air<AB> for CounterAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0).expect("row_slice returned None")[0];
+        let next = main.row_slice(1).expect("row_slice returned None")[0];
+        builder.when_first_row().assert_zero(local);
+        builder.when_transition().assert_eq(next, local + Fr::one());
+    }
+}
+
+type SquareCounterAir = VerticalAir<SquareAir, CounterAir>;
+
+fn air() -> SquareCounterAir {
+    VerticalAir::new(SquareAir {}, CounterAir {})
+}
+
+/// Three squares on top of a counter running over the remaining five rows.
+fn traces(square: impl Fn(u32) -> u32, start: u32) -> (RowMajorMatrix<Fr>, RowMajorMatrix<Fr>) {
+    let squares = RowMajorMatrix::new(
+        (0..3)
+            .flat_map(|i| [Fr::from_u32(i), Fr::from_u32(square(i))])
+            .collect(),
+        2,
+    );
+    let counter = RowMajorMatrix::new((start..start + 5).map(Fr::from_u32).collect(), 1);
+    (squares, counter)
+}
+
+fn check<A>(air: &A, main: RowMajorMatrix<Fr>) -> Result<(), ConstraintError<Fr, Fr>>
+where
+    A: BaseAirWithPublicValues<Fr> + for<'a> Air<DebugConstraintBuilder<'a, Fr, Fr>>,
+{
+    check_constraints(air, &main, &[])
+}
+
+#[test]
+fn stacked_airs_pass() {
+    let air = air();
+    let (squares, counter) = traces(|i| i * i, 0);
+    let main = air.stack_traces(&squares, &counter);
+    assert_eq!((main.width(), main.height()), (4, 8));
+    assert_eq!(
+        main.row_slice(3).unwrap().to_vec(),
+        [0, 0, 0, 1].map(Fr::from_u32)
+    );
+
+    let (top, bottom) = air.split_trace(&main, &Fr::one());
+    assert_eq!(top.values, squares.values);
+    assert_eq!(bottom.values, counter.values);
+    assert_eq!(check(&air, main), Ok(()));
+}
+
+#[test]
+fn top_constraints_apply_to_the_top_segment() {
+    let air = air();
+    let (squares, counter) = traces(|i| if i == 1 { 2 } else { i * i }, 0);
+    let error = check(&air, air.stack_traces(&squares, &counter)).unwrap_err();
+    assert!(error
+        .to_string()
+        .starts_with("constraints had nonzero value on row 1"));
+}
+
+#[test]
+fn bottom_first_row_is_the_segment_start() {
+    let air = air();
+    let (squares, counter) = traces(|i| i * i, 1);
+    let error = check(&air, air.stack_traces(&squares, &counter)).unwrap_err();
+    assert!(error
+        .to_string()
+        .starts_with("constraints had nonzero value on row 3"));
+}
+
+#[test]
+fn segments_cannot_interleave() {
+    let air = air();
+    let (squares, counter) = traces(|i| i * i, 0);
+    let mut main = air.stack_traces(&squares, &counter);
+    main.values[5 * 4 + 2] = Fr::one();
+    let error = check(&air, main).unwrap_err();
+    assert!(error
+        .to_string()
+        .starts_with("constraints had nonzero value on row 4"));
+}
+
+#[test]
+fn constraints_are_scoped_by_segment() {
+    let names: Vec<_> = get_named_symbolic_constraints(&air())
+        .into_iter()
+        .map(|constraint| constraint.name)
+        .collect();
+    assert_eq!(
+        names,
+        ["selectors"; 6]
+            .into_iter()
+            .chain(["top", "bottom", "bottom"])
+            .collect::<Vec<_>>()
+    );
+}
+
+/// Alternates between zero and one, following a periodic column.
+pub struct AlternatingAir {}
+
+/// Must hold seven on row two.
+pub struct SevenOnRowTwoAir {}
+
+impl BaseAir<Fr> for AlternatingAir {
+    fn width(&self) -> usize {
+        1
+    }
+
+    fn periodic_columns(&self) -> Vec<Vec<Fr>> {
+        vec![vec![Fr::zero(), Fr::one()]]
+    }
+}
+
+impl BaseAir<Fr> for SevenOnRowTwoAir {
+    fn width(&self) -> usize {
+        1
+    }
+}
+
+impl BaseAirWithPublicValues<Fr> for AlternatingAir {}
+impl BaseAirWithPublicValues<Fr> for SevenOnRowTwoAir {}
+
+impl<AB: PeriodicAirBuilder<F = Fr>> Air<AB> for AlternatingAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0).expect("row_slice returned None")[0];
+        let expected = builder.periodic_values()[0];
+        builder.assert_eq(local, expected);
+    }
+}
+
+impl<AB: AirBuilder<F = Fr>> Air<AB> for SevenOnRowTwoAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0).expect("row_slice returned None")[0];
+        let seven = builder.from_u32(7);
+        builder.when_row(2).assert_eq(local, seven);
+    }
+}
+
+fn squares(n: u32) -> RowMajorMatrix<Fr> {
+    RowMajorMatrix::new(
+        (0..n)
+            .flat_map(|i| [Fr::from_u32(i), Fr::from_u32(i * i)])
+            .collect(),
+        2,
+    )
+}
+
+#[test]
+fn bottom_periodic_columns_start_with_the_segment() {
+    let air = VerticalAir::new(SquareAir {}, AlternatingAir {});
+    let alternating = RowMajorMatrix::new((0..4).map(|i| Fr::from_u32(i % 2)).collect(), 1);
+    let main = air.stack_traces(&squares(4), &alternating);
+    assert_eq!(check(&air, main), Ok(()));
+}
+
+#[test]
+fn misaligned_bottom_periodic_columns_are_rejected() {
+    let air = VerticalAir::new(SquareAir {}, AlternatingAir {});
+    // `stack_traces` refuses a top segment of odd height, so stack by hand. The bottom rows follow
+    // the periodic column from the first row of the trace, which starts them on one.
+    let values = (0..3)
+        .flat_map(|i| [i, i * i, 1, 0])
+        .chain((3..8).flat_map(|i| [i % 2, 0, 0, u32::from(i == 3)]))
+        .map(Fr::from_u32)
+        .collect();
+    let main = RowMajorMatrix::new(values, 4);
+    let error = check(&air, main).unwrap_err();
+    assert!(error
+        .to_string()
+        .starts_with("constraints had nonzero value on row 3"));
+}
+
+#[test]
+fn top_row_selectors_count_from_the_first_row() {
+    let air = VerticalAir::new(SevenOnRowTwoAir {}, SquareAir {});
+    let sevens = RowMajorMatrix::new(vec![Fr::from_u32(7); 3], 1);
+    let main = air.stack_traces(&sevens, &squares(5));
+    assert_eq!(check(&air, main), Ok(()));
+}
+
+#[test]
+#[should_panic(
+    expected = "row selectors are not supported in the bottom segment of a VerticalAir (is_row(2))"
+)]
+fn bottom_row_selectors_are_rejected() {
+    get_symbolic_constraints::<Fr, _>(&VerticalAir::new(SquareAir {}, SevenOnRowTwoAir {}));
+}
+
+/// Sends each even column and receives the odd column after it on the same bus, so the columns of
+/// each pair must be permutations of each other.
+pub struct ShuffleAir {
+    pairs: usize,
+}
+
+impl BaseAir<Fr> for ShuffleAir {
+    fn width(&self) -> usize {
+        2 * self.pairs
+    }
+
+    fn permutation_width(&self) -> usize {
+        logup_permutation_width(self.interactions().len())
+    }
+
+    fn num_challenges(&self) -> usize {
+        LOGUP_NUM_CHALLENGES
+    }
+}
+
+impl BaseAirWithPublicValues<Fr> for ShuffleAir {}
+
+impl InteractionAir<Fr> for ShuffleAir {
+    fn interactions(&self) -> Vec<Interaction<Fr>> {
+        (0..self.pairs)
+            .flat_map(|i| {
+                let one = VirtualPairCol::constant(Fr::one());
+                [
+                    Interaction::send(i, vec![VirtualPairCol::single_main(2 * i)], one.clone()),
+                    Interaction::receive(i, vec![VirtualPairCol::single_main(2 * i + 1)], one),
+                ]
+            })
+            .collect()
+    }
+}
+
+impl MultiPhaseAir<Fr, EF> for ShuffleAir {
+    fn generate_permutation_trace(
+        &self,
+        main: &RowMajorMatrix<Fr>,
+        challenges: &[EF],
+    ) -> RowMajorMatrix<EF> {
+        generate_logup_trace(&self.interactions(), None, main, challenges)
+    }
+}
+
+impl<AB> Air<AB> for ShuffleAir
+where
+    AB: PermutationAirBuilder<F = Fr, EF = EF> + PairBuilder,
+{
+    fn eval(&self, builder: &mut AB) {
+        eval_logup(builder, &self.interactions(), Some(EF::zero().into()));
+    }
+}
+
+/// Each row holds `pairs` pairs of the sent and the received value.
+fn shuffles(pairs: usize, rows: &[[u32; 2]]) -> RowMajorMatrix<Fr> {
+    let values = rows
+        .iter()
+        .flat_map(|pair| pair.repeat(pairs))
+        .map(Fr::from_u32)
+        .collect();
+    RowMajorMatrix::new(values, 2 * pairs)
+}
+
+#[test]
+fn stacked_logup_airs_use_their_own_auxiliary_columns() {
+    let air = VerticalAir::new(ShuffleAir { pairs: 1 }, ShuffleAir { pairs: 2 });
+    assert_eq!(BaseAir::<Fr>::permutation_width(&air), 5);
+    let main = air.stack_traces(
+        &shuffles(1, &[[1, 2], [2, 1]]),
+        &shuffles(2, &[[3, 5], [4, 3], [5, 4]]),
+    );
+    let trace = generate_multi_phase_trace(&air, main, &mut SeededChallenger::<Fr>::new(1));
+    assert_eq!(try_check_multi_phase_constraints(&air, &trace, &[]), Ok(()));
+
+    let main = air.stack_traces(
+        &shuffles(1, &[[1, 2], [2, 1]]),
+        &shuffles(2, &[[3, 5], [4, 3], [5, 6]]),
+    );
+    let trace = generate_multi_phase_trace(&air, main, &mut SeededChallenger::<Fr>::new(1));
+    let error = try_check_multi_phase_constraints(&air, &trace, &[]).unwrap_err();
+    assert!(error
+        .to_string()
+        .starts_with("extension constraints had nonzero value on row 4"));
+}
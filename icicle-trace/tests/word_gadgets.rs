@@ -0,0 +1,187 @@
+use core::borrow::{Borrow, BorrowMut};
+
+use icicle_babybear::field::ScalarField as Fr;
+use icicle_core::bignum::BigNum;
+use icicle_trace::word::{self, Word, Word32, Word32Bits};
+use icicle_trace::{
+    check_air_field_requirements, check_constraints, Air, AirBuilder, AirColumns, AirConfig,
+    BaseAir, BaseAirWithPublicValues, ConstraintError, SymbolicAirBuilder, SymbolicVariable,
+};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+
+#[repr(C)]
+#[derive(AirColumns)]
+pub struct WordCols<T> {
+    a: Word32<T>,
+    a_bits: Word32Bits<T>,
+    b: Word32<T>,
+    b_bits: Word32Bits<T>,
+    sum: Word32<T>,
+    carries: [T; 2],
+    diff: Word32<T>,
+    borrows: [T; 2],
+    is_less_than: T,
+    and: Word32Bits<T>,
+    or: Word32Bits<T>,
+    xor: Word32Bits<T>,
+    not: Word32Bits<T>,
+    rotated: Word32<T>,
+    shifted_right: Word32<T>,
+    shifted_left: Word32<T>,
+}
+
+const NUM_WORD_COLS: usize = <WordCols<u8> as AirColumns<u8>>::NUM_COLUMNS;
+
+/// Runs every word gadget on the 32-bit words `a` and `b` of each row.
+pub struct WordAir {}
+
+impl BaseAir<Fr> for WordAir {
+    fn width(&self) -> usize {
+        NUM_WORD_COLS
+    }
+}
+
+impl BaseAirWithPublicValues<Fr> for WordAir {}
+
+fn check(main: RowMajorMatrix<Fr>) -> Result<(), ConstraintError<Fr, Fr>> {
+    check_constraints(&WordAir {}, &main, &[])
+}
+
+impl<AB: AirBuilder<F = Fr>> Air<AB> for WordAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let row = main.row_slice(0).expect("row_slice returned None");
+        let local: &WordCols<AB::Var> = (*row).borrow();
+
+        word::limbs_from_bits(builder, &local.a, &local.a_bits);
+        word::limbs_from_bits(builder, &local.b, &local.b_bits);
+        word::add(builder, &local.sum, &local.a, &local.b, &local.carries);
+        let is_less_than =
+            word::less_than(builder, &local.a, &local.b, &local.diff, &local.borrows);
+        builder.assert_eq(local.is_less_than, is_less_than);
+        word::and(builder, &local.and, &local.a_bits, &local.b_bits);
+        word::or(builder, &local.or, &local.a_bits, &local.b_bits);
+        word::xor(builder, &local.xor, &local.a_bits, &local.b_bits);
+        word::not(builder, &local.not, &local.a_bits);
+        word::rotate_right(builder, &local.rotated, &local.a_bits, 7);
+        word::shift_right(builder, &local.shifted_right, &local.a_bits, 3);
+        word::shift_left(builder, &local.shifted_left, &local.a_bits, 20);
+    }
+}
+
+fn fill(cols: &mut WordCols<Fr>, a: u32, b: u32) {
+    let (a, b) = (a as u64, b as u64);
+    cols.a = Word32::from_u64(a);
+    cols.a_bits = Word32Bits::from_u64(a);
+    cols.b = Word32::from_u64(b);
+    cols.b_bits = Word32Bits::from_u64(b);
+    (cols.sum, cols.carries) = word::add_witness(a, b);
+    (cols.diff, cols.borrows) = word::less_than_witness(a, b);
+    cols.is_less_than = cols.borrows[1];
+    cols.and = word::and_witness(a, b);
+    cols.or = word::or_witness(a, b);
+    cols.xor = word::xor_witness(a, b);
+    cols.not = word::not_witness(a);
+    cols.rotated = word::rotate_right_witness(a, 7);
+    cols.shifted_right = word::shift_right_witness(a, 3);
+    cols.shifted_left = word::shift_left_witness(a, 20);
+}
+
+fn trace(rows: &[(u32, u32)]) -> RowMajorMatrix<Fr> {
+    let mut trace =
+        RowMajorMatrix::new(vec![Fr::zero(); rows.len() * NUM_WORD_COLS], NUM_WORD_COLS);
+    for (row, &(a, b)) in trace.values.chunks_mut(NUM_WORD_COLS).zip(rows) {
+        fill(row.borrow_mut(), a, b);
+    }
+    trace
+}
+
+const ROWS: [(u32, u32); 4] = [
+    (0xFFFF_FFFF, 1),
+    (0x1234_5678, 0x9ABC_DEF0),
+    (0x8000_0000, 0x8000_0000),
+    (0, 0xFFFF_FFFF),
+];
+
+#[test]
+fn gadgets_match_native_arithmetic() {
+    assert_eq!(check(trace(&ROWS)), Ok(()));
+    check_air_field_requirements::<Fr, _>(&WordAir {}).unwrap();
+
+    let trace = trace(&ROWS);
+    let row = trace.row_slice(1).expect("row_slice returned None");
+    let cols: &WordCols<Fr> = (*row).borrow();
+    assert_eq!(cols.is_less_than, Fr::one());
+    assert_eq!(cols.sum, Word32::from_u64(0x1234_5678 + 0x9ABC_DEF0));
+}
+
+#[test]
+fn wrong_carry_is_caught() {
+    let mut trace = trace(&ROWS);
+    let row = &mut trace.values[2 * NUM_WORD_COLS..3 * NUM_WORD_COLS];
+    let cols: &mut WordCols<Fr> = row.borrow_mut();
+    cols.carries[1] = Fr::zero();
+    let error = check(trace).unwrap_err();
+    assert!(error
+        .to_string()
+        .starts_with("values didn't match on row 2"));
+}
+
+/// Adds two 80-bit words, held as five limbs each: `a`, `b`, `sum` and the carries.
+pub struct WideAddAir {}
+
+impl BaseAir<Fr> for WideAddAir {
+    fn width(&self) -> usize {
+        4 * 5
+    }
+}
+
+impl BaseAirWithPublicValues<Fr> for WideAddAir {}
+
+impl<AB: AirBuilder<F = Fr>> Air<AB> for WideAddAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0).expect("row_slice returned None").to_vec();
+        let word = |i: usize| Word::<_, 5> {
+            limbs: core::array::from_fn(|j| local[5 * i + j]),
+        };
+        let carries = word(3).limbs;
+        word::add(builder, &word(2), &word(0), &word(1), &carries);
+    }
+}
+
+#[test]
+fn five_limb_words_carry_past_64_bits() {
+    let a = Word::<Fr, 5>::from_u64(u64::MAX);
+    assert_eq!(a.limbs[4], Fr::zero());
+    assert_eq!(
+        word::shift_left_witness::<Fr, 5>(1, 63).limbs[3],
+        Fr::from_u32(0x8000)
+    );
+
+    let (sum, carries) = word::add_witness::<Fr, 5>(u64::MAX, 1);
+    assert_eq!(sum.limbs, [0, 0, 0, 0, 1].map(Fr::from_u32));
+    let values = a
+        .limbs
+        .into_iter()
+        .chain(Word::<Fr, 5>::from_u64(1).limbs)
+        .chain(sum.limbs)
+        .chain(carries)
+        .collect();
+    let main = RowMajorMatrix::new(values, 20);
+    assert_eq!(check_constraints(&WideAddAir {}, &main, &[]), Ok(()));
+}
+
+#[test]
+#[should_panic(expected = "limb and bit forms have different widths")]
+fn shift_of_mismatched_widths_is_rejected() {
+    let mut builder = SymbolicAirBuilder::<Fr>::new(&AirConfig::from_air(&WordAir {}));
+    let main = builder.main();
+    let row = main.row_slice(0).expect("row_slice returned None");
+    let local: &WordCols<SymbolicVariable<Fr>> = (*row).borrow();
+    let out: Word<_, 4> = Word {
+        limbs: [local.shifted_right.limbs[0]; 4],
+    };
+    word::shift_right(&mut builder, &out, &local.a_bits, 3);
+}